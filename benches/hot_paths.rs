@@ -0,0 +1,58 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use lscolors::{LsColors, Style, DEFAULT_LS_COLORS};
+
+/// A synthetic list of file names, with extensions that are (mostly) in the default palette.
+fn filenames(count: usize) -> Vec<String> {
+    const EXTENSIONS: [&str; 12] = [
+        "rs", "toml", "tar.gz", "PNG", "jpg", "mp3", "md", "zip", "txt", "deb", "flac", "",
+    ];
+
+    (0..count)
+        .map(|i| match EXTENSIONS[i % EXTENSIONS.len()] {
+            "" => format!("file{}", i),
+            extension => format!("file{}.{}", i, extension),
+        })
+        .collect()
+}
+
+fn from_string(c: &mut Criterion) {
+    c.bench_function("from_string (default palette)", |b| {
+        b.iter(|| LsColors::from_string(black_box(DEFAULT_LS_COLORS)))
+    });
+}
+
+fn lookup(c: &mut Criterion) {
+    let lscolors = LsColors::from_string(DEFAULT_LS_COLORS);
+    let filenames = filenames(10_000);
+
+    c.bench_function("style_for_path_with_metadata (10k names)", |b| {
+        b.iter(|| {
+            for filename in &filenames {
+                black_box(lscolors.style_for_path_with_metadata(black_box(filename), None));
+            }
+        })
+    });
+}
+
+fn from_ansi_sequence(c: &mut Criterion) {
+    let codes = [
+        "01;34",
+        "00;38;5;115",
+        "38;2;255;0;100;48;5;4",
+        "4:3;58;2;1;2;3",
+        "30;41;1;4;5;7;9",
+        "38:2::255:128:0",
+    ];
+
+    c.bench_function("Style::from_ansi_sequence", |b| {
+        b.iter(|| {
+            for code in &codes {
+                black_box(Style::from_ansi_sequence(black_box(code)));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, from_string, lookup, from_ansi_sequence);
+criterion_main!(benches);
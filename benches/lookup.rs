@@ -0,0 +1,56 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use lscolors::LsColors;
+
+/// A global allocator that counts the number of allocations.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const FILENAMES: [&str; 6] = [
+    "Cargo.toml",
+    "src/lib.rs",
+    "archive.tar.GZ",
+    "photo.png",
+    "README",
+    "some/deeply/nested/folder/without_extension",
+];
+
+fn lookup_without_metadata(c: &mut Criterion) {
+    let lscolors = LsColors::default();
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for filename in &FILENAMES {
+        black_box(lscolors.style_for_path_with_metadata(filename, None));
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    assert_eq!(0, allocations, "suffix lookups should not allocate");
+
+    c.bench_function("style_for_path_with_metadata (no metadata)", |b| {
+        b.iter(|| {
+            for filename in &FILENAMES {
+                black_box(lscolors.style_for_path_with_metadata(black_box(filename), None));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, lookup_without_metadata);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use lscolors::{LsColors, Style};
+
+/// Build a large `LS_COLORS` value with the given number of suffix entries.
+fn large_ls_colors(entries: usize) -> String {
+    (0..entries)
+        .map(|i| format!("*.ext{}=01;3{}:", i, i % 8))
+        .collect()
+}
+
+/// The previous implementation: a reverse linear scan over all (lowercase) suffixes.
+fn linear_scan<'a>(suffixes: &[(String, &'a Style)], filename: &str) -> Option<&'a Style> {
+    let filename = filename.to_ascii_lowercase();
+    suffixes
+        .iter()
+        .rev()
+        .find(|(suffix, _)| filename.ends_with(suffix.as_str()))
+        .map(|(_, style)| *style)
+}
+
+fn suffix_lookup(c: &mut Criterion) {
+    let lscolors = LsColors::from_string(&large_ls_colors(1000));
+    let suffixes: Vec<_> = lscolors
+        .suffixes()
+        .map(|(suffix, style)| (suffix.to_ascii_lowercase(), style))
+        .collect();
+
+    let filenames = ["archive.ext500", "notes.txt", "photo.PNG", "README"];
+
+    let mut group = c.benchmark_group("suffix lookup (1000 entries)");
+    group.bench_function("linear scan", |b| {
+        b.iter(|| {
+            for filename in &filenames {
+                black_box(linear_scan(&suffixes, black_box(filename)));
+            }
+        })
+    });
+    group.bench_function("trie", |b| {
+        b.iter(|| {
+            for filename in &filenames {
+                black_box(lscolors.style_for_path_with_metadata(black_box(filename), None));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, suffix_lookup);
+criterion_main!(benches);
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use lscolors::Style;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(code) = std::str::from_utf8(data) {
+        if let Some(style) = Style::from_ansi_sequence(code) {
+            let _ = Style::from_ansi_sequence(&style.to_ansi_sequence());
+        }
+    }
+});
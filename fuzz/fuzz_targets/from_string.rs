@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use lscolors::LsColors;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let lscolors = LsColors::from_string(input);
+        let _ = lscolors.to_string();
+        let _ = lscolors.style_for_path("archive.tar.gz");
+        let _ = lscolors.sample_lines();
+    }
+});
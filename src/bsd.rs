@@ -0,0 +1,103 @@
+//! Parser for the `LSCOLORS` format used by the BSD and macOS versions of `ls` (e.g.
+//! `exfxcxdxbxegedabagacad`). See the `ls(1)` manual page of FreeBSD.
+
+use crate::Indicator;
+
+/// The indicators in the order of the `LSCOLORS` positions.
+const INDICATORS: [Indicator; 11] = [
+    Indicator::Directory,
+    Indicator::SymbolicLink,
+    Indicator::Socket,
+    Indicator::FIFO,
+    Indicator::ExecutableFile,
+    Indicator::BlockDevice,
+    Indicator::CharacterDevice,
+    Indicator::Setuid,
+    Indicator::Setgid,
+    Indicator::StickyAndOtherWritable,
+    Indicator::OtherWritable,
+];
+
+/// Decode a single color letter into an SGR color index (`0`-`7`) and whether it is uppercase.
+/// Like BSD `ls`, the legacy digits `0`-`7` are accepted as well. Returns `None` for the default
+/// color (`x`) and invalid characters.
+fn decode_color(c: char) -> Option<(u8, bool)> {
+    match c {
+        'a'..='h' => Some((c as u8 - b'a', false)),
+        'A'..='H' => Some((c as u8 - b'A', true)),
+        '0'..='7' => Some((c as u8 - b'0', false)),
+        _ => None,
+    }
+}
+
+/// Parse an `LSCOLORS` value into `(key, value)` pairs in the `LS_COLORS` format, e.g. the
+/// leading `Ex` becomes `("di", "01;34")`. Each position is a foreground and a background letter:
+/// `a`-`h` for black, red, green, brown, blue, magenta, cyan and light grey, `x` for the default
+/// color. An uppercase foreground is bold, and (like in FreeBSD `ls`) an uppercase background is
+/// underlined. Missing positions of a short value are left unset, and extra characters of a long
+/// value are ignored. Positions with default colors only are skipped.
+pub fn parse(input: &str) -> Vec<(&'static str, String)> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut entries = vec![];
+
+    for (indicator, pair) in INDICATORS.iter().zip(chars.chunks_exact(2)) {
+        let mut codes = vec![];
+
+        let foreground = decode_color(pair[0]);
+        let background = decode_color(pair[1]);
+        if let Some((_, true)) = foreground {
+            codes.push("01".to_string());
+        }
+        if let Some((_, true)) = background {
+            codes.push("04".to_string());
+        }
+        if let Some((color, _)) = foreground {
+            codes.push((30 + color).to_string());
+        }
+        if let Some((color, _)) = background {
+            codes.push((40 + color).to_string());
+        }
+
+        if !codes.is_empty() {
+            entries.push((indicator.to_str(), codes.join(";")));
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn parse_default_macos_colors() {
+        assert_eq!(
+            vec![
+                ("di", "34".to_string()),
+                ("ln", "35".to_string()),
+                ("so", "32".to_string()),
+                ("pi", "33".to_string()),
+                ("ex", "31".to_string()),
+                ("bd", "34;46".to_string()),
+                ("cd", "34;43".to_string()),
+                ("su", "30;41".to_string()),
+                ("sg", "30;46".to_string()),
+                ("tw", "30;42".to_string()),
+                ("ow", "30;43".to_string()),
+            ],
+            parse("exfxcxdxbxegedabagacad")
+        );
+    }
+
+    #[test]
+    fn parse_bold_and_short_values() {
+        assert_eq!(
+            vec![("di", "01;36".to_string()), ("ln", "04;35;41".to_string())],
+            parse("GxfBxx")
+        );
+        assert_eq!(vec![("di", "34".to_string())], parse("exf"));
+        assert_eq!(Vec::<(&str, String)>::new(), parse(""));
+        assert_eq!(11, parse(&"ex".repeat(20)).len());
+    }
+}
@@ -0,0 +1,114 @@
+//! Parser for the database format used by `dircolors` (see `dircolors --print-database`).
+use std::io::{self, BufRead};
+
+use crate::Indicator;
+
+/// Map a `dircolors` keyword like `DIR` to the corresponding indicator.
+fn indicator_for_keyword(keyword: &str) -> Option<Indicator> {
+    match keyword.to_ascii_uppercase().as_str() {
+        "NORMAL" | "NORM" => Some(Indicator::Normal),
+        "FILE" => Some(Indicator::RegularFile),
+        "RESET" => Some(Indicator::Reset),
+        "DIR" => Some(Indicator::Directory),
+        "LNK" | "LINK" | "SYMLINK" => Some(Indicator::SymbolicLink),
+        "ORPHAN" => Some(Indicator::OrphanedSymbolicLink),
+        "MISSING" => Some(Indicator::MissingFile),
+        "FIFO" | "PIPE" => Some(Indicator::FIFO),
+        "SOCK" => Some(Indicator::Socket),
+        "BLK" | "BLOCK" => Some(Indicator::BlockDevice),
+        "CHR" | "CHAR" => Some(Indicator::CharacterDevice),
+        "DOOR" => Some(Indicator::Door),
+        "EXEC" => Some(Indicator::ExecutableFile),
+        "LEFT" | "LEFTCODE" => Some(Indicator::LeftCode),
+        "RIGHT" | "RIGHTCODE" => Some(Indicator::RightCode),
+        "END" | "ENDCODE" => Some(Indicator::EndCode),
+        "SUID" | "SETUID" => Some(Indicator::Setuid),
+        "SGID" | "SETGID" => Some(Indicator::Setgid),
+        "STICKY" => Some(Indicator::Sticky),
+        "OTHER_WRITABLE" | "OWR" => Some(Indicator::OtherWritable),
+        "STICKY_OTHER_WRITABLE" | "OWT" => Some(Indicator::StickyAndOtherWritable),
+        "CAPABILITY" => Some(Indicator::Capabilities),
+        "MULTIHARDLINK" => Some(Indicator::MultipleHardLinks),
+        "CLRTOEOL" => Some(Indicator::ClearLine),
+        _ => None,
+    }
+}
+
+/// Strip a `#` comment from a line. Like `dircolors`, a `#` only starts a comment at the
+/// beginning of the line or after whitespace.
+fn strip_comment(line: &str) -> &str {
+    // Treat the start of the line like whitespace
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        if c == '#' && previous.is_whitespace() {
+            return &line[..i];
+        }
+        previous = c;
+    }
+    line
+}
+
+/// Parse a `dircolors` database into `(key, value)` pairs in the `LS_COLORS` format, e.g.
+/// `DIR 01;34` becomes `("di", "01;34")` and `.tar 01;31` becomes `("*.tar", "01;31")`.
+/// `TERM`, `COLORTERM`, `OPTIONS`, `COLOR` and `EIGHTBIT` lines, as well as unknown keywords,
+/// are ignored.
+pub fn parse<R: BufRead>(reader: R) -> io::Result<Vec<(String, String)>> {
+    let mut entries = vec![];
+
+    for line in reader.lines() {
+        let line = line?;
+        let mut words = strip_comment(&line).split_whitespace();
+
+        let (keyword, value) = match (words.next(), words.next()) {
+            (Some(keyword), Some(value)) => (keyword, value),
+            _ => continue,
+        };
+
+        if keyword.starts_with('.') {
+            entries.push((format!("*{}", keyword), value.to_string()));
+        } else if keyword.starts_with('*') {
+            entries.push((keyword.to_string(), value.to_string()));
+        } else if let Some(indicator) = indicator_for_keyword(keyword) {
+            entries.push((indicator.to_str().to_string(), value.to_string()));
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn parse_database() {
+        let database = "\
+# Configuration file for dircolors
+TERM xterm*
+COLORTERM ?*
+
+DIR 01;34 # directories
+LINK 01;36
+.tar 01;31
+*README 01;33
+*#backup# 00;90
+UNKNOWN 01
+";
+        let entries = parse(database.as_bytes()).unwrap();
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("di", "01;34"),
+                ("ln", "01;36"),
+                ("*.tar", "01;31"),
+                ("*README", "01;33"),
+                ("*#backup#", "00;90"),
+            ],
+            entries
+        );
+    }
+}
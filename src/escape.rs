@@ -0,0 +1,177 @@
+//! Escape sequences in `LS_COLORS` values, as used for the `lc`, `rc` and `ec` codes (e.g.
+//! `lc=\e[`). See `get_funky_string` in GNU `ls`.
+
+/// Decode a value like `\e[` or `^[[` into the raw string it represents. Supported are the C-style
+/// escapes (`\e`, `\n`, ..), octal (`\033`) and hexadecimal (`\x1b`) escapes, and caret notation
+/// (`^[`). Any other character after a backslash is taken literally.
+///
+/// Unlike `ls`, which writes octal and hexadecimal escapes as raw bytes, codes from `0x80` to
+/// `0xff` (like the 8-bit CSI `\233` or `\x9b`) become the Unicode character with that code point
+/// (U+0080 to U+00FF), since the result has to be valid UTF-8.
+pub fn unescape(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('a') => result.push('\x07'),
+                Some('b') => result.push('\x08'),
+                Some('e') => result.push('\x1b'),
+                Some('f') => result.push('\x0c'),
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some('t') => result.push('\t'),
+                Some('v') => result.push('\x0b'),
+                Some('?') => result.push('\x7f'),
+                Some('_') => result.push(' '),
+                Some(digit @ '0'..='7') => {
+                    let mut code = digit.to_digit(8).unwrap_or(0);
+                    for _ in 0..2 {
+                        match chars.peek().and_then(|c| c.to_digit(8)) {
+                            Some(digit) => code = code * 8 + digit,
+                            None => break,
+                        }
+                        chars.next();
+                    }
+                    result.extend(char::from_u32(code & 0xff));
+                }
+                Some('x') => {
+                    let mut code = 0;
+                    for _ in 0..2 {
+                        match chars.peek().and_then(|c| c.to_digit(16)) {
+                            Some(digit) => code = code * 16 + digit,
+                            None => break,
+                        }
+                        chars.next();
+                    }
+                    result.extend(char::from_u32(code));
+                }
+                Some(c) => result.push(c),
+                None => {}
+            },
+            '^' => match chars.next() {
+                Some('?') => result.push('\x7f'),
+                Some(c @ '@'..='~') => result.extend(char::from_u32(u32::from(c) & 0x1f)),
+                Some(c) => {
+                    result.push('^');
+                    result.push(c);
+                }
+                None => result.push('^'),
+            },
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Find the first `separator` in `value` that is not escaped with a backslash (like `\:`).
+fn find_unescaped(value: &str, separator: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == separator {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Split `value` at the first `separator` that is not escaped with a backslash. The escape
+/// sequences themselves are kept, see [`unescape`].
+pub fn split_once_unescaped(value: &str, separator: char) -> Option<(&str, &str)> {
+    let i = find_unescaped(value, separator)?;
+    Some((&value[..i], &value[i + separator.len_utf8()..]))
+}
+
+/// Split `value` at each `separator` that is not escaped with a backslash, e.g. to split
+/// `*.a\:b=31:di=34` into its entries.
+pub fn split_unescaped(value: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut rest = Some(value);
+    std::iter::from_fn(move || {
+        let value = rest?;
+        match split_once_unescaped(value, separator) {
+            Some((head, tail)) => {
+                rest = Some(tail);
+                Some(head)
+            }
+            None => {
+                rest = None;
+                Some(value)
+            }
+        }
+    })
+}
+
+/// Encode a raw string so that it can be used as an `LS_COLORS` value, the inverse of
+/// [`unescape`].
+pub fn escape(raw: &str) -> String {
+    let mut result = String::new();
+
+    for c in raw.chars() {
+        match c {
+            '\x1b' => result.push_str("\\e"),
+            '\\' => result.push_str("\\\\"),
+            '^' => result.push_str("\\^"),
+            // These would end the entry or value early
+            ':' | '=' => result.push_str(&format!("\\{:03o}", u32::from(c))),
+            '\x7f' => result.push_str("^?"),
+            c if c.is_ascii_control() => {
+                result.push('^');
+                result.push(char::from(c as u8 | 0x40));
+            }
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, split_once_unescaped, split_unescaped, unescape};
+
+    #[test]
+    fn unescape_codes() {
+        assert_eq!("\x1b[", unescape("\\e["));
+        assert_eq!("\x1b[", unescape("^[["));
+        assert_eq!("\x1b[", unescape("\\033["));
+        assert_eq!("\x1b[", unescape("\\x1b["));
+        assert_eq!("m", unescape("m"));
+        assert_eq!("a b\\^", unescape("a\\_b\\\\\\^"));
+        assert_eq!("\x7f\x7f", unescape("\\?^?"));
+        assert_eq!("^", unescape("^"));
+    }
+
+    #[test]
+    fn unescape_high_codes() {
+        assert_eq!("\u{9b}", unescape("\\x9b"));
+        assert_eq!("\u{9b}", unescape("\\233"));
+        assert_eq!("\u{ff}", unescape("\\377"));
+        assert_eq!(2, unescape("\\233").len());
+    }
+
+    #[test]
+    fn escape_round_trip() {
+        for raw in &["\x1b[", "m", "\x1b[0m", "a:b=c\\d^e", "\t\x7f"] {
+            assert_eq!(*raw, unescape(&escape(raw)));
+        }
+        assert_eq!("\\e[", escape("\x1b["));
+    }
+
+    #[test]
+    fn split_at_unescaped_separators() {
+        let entries: Vec<_> = split_unescaped("*.a\\:b=31:di=34::\\\\:x", ':').collect();
+        assert_eq!(vec!["*.a\\:b=31", "di=34", "", "\\\\", "x"], entries);
+
+        assert_eq!(
+            Some(("*.a\\=b", "01;31")),
+            split_once_unescaped("*.a\\=b=01;31", '=')
+        );
+        assert_eq!(None, split_once_unescaped("di", '='));
+    }
+}
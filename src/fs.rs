@@ -1,4 +1,6 @@
+use std::ffi::OsStr;
 use std::fs;
+use std::path::Path;
 
 #[cfg(any(unix, target_os = "redox"))]
 use std::os::unix::fs::MetadataExt;
@@ -22,3 +24,71 @@ pub fn nlink(md: &fs::Metadata) -> u64 {
     #[cfg(not(any(unix, target_os = "redox")))]
     return 1;
 }
+
+/// Get the raw bytes of a file name. On Unix, this works for arbitrary (non-UTF-8) file names.
+/// On other platforms, `None` is returned for file names that are not valid UTF-8.
+pub fn name_bytes(name: &OsStr) -> Option<&[u8]> {
+    #[cfg(any(unix, target_os = "redox"))]
+    return Some(std::os::unix::ffi::OsStrExt::as_bytes(name));
+
+    #[cfg(not(any(unix, target_os = "redox")))]
+    return name.to_str().map(str::as_bytes);
+}
+
+/// The `FILE_ATTRIBUTE_READONLY` flag from the Windows API.
+pub const FILE_ATTRIBUTE_READONLY: u32 = 0x1;
+
+/// The `FILE_ATTRIBUTE_HIDDEN` flag from the Windows API.
+pub const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+
+/// Get the Windows file attributes (`FILE_ATTRIBUTE_*`) from some metadata, or 0 on other
+/// platforms.
+#[allow(unused_variables)]
+pub fn file_attributes(md: &fs::Metadata) -> u32 {
+    #[cfg(windows)]
+    return std::os::windows::fs::MetadataExt::file_attributes(md);
+
+    #[cfg(not(windows))]
+    return 0;
+}
+
+/// Check whether a file is a door (only exists on Solaris and illumos).
+#[allow(unused_variables)]
+pub fn is_door(md: &fs::Metadata) -> bool {
+    // S_IFMT and S_IFDOOR from <sys/stat.h>
+    #[cfg(any(target_os = "solaris", target_os = "illumos"))]
+    return md.mode() & 0o170000 == 0o150000;
+
+    #[cfg(not(any(target_os = "solaris", target_os = "illumos")))]
+    return false;
+}
+
+/// Check whether a file has capabilities set (via the `security.capability` extended attribute).
+#[cfg(target_os = "linux")]
+pub fn has_capabilities(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return false,
+    };
+    let name = b"security.capability\0";
+
+    // Passing a zero size only queries the length of the attribute value
+    let size = unsafe {
+        libc::lgetxattr(
+            path.as_ptr(),
+            name.as_ptr() as *const std::os::raw::c_char,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    size > 0
+}
+
+/// File capabilities are only supported on Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn has_capabilities(_path: &Path) -> bool {
+    false
+}
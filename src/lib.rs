@@ -2,6 +2,8 @@
 //!
 //! # Example
 //! ```
+//! # #[cfg(feature = "std")]
+//! # {
 //! use lscolors::{LsColors, Style};
 //!
 //! let lscolors = LsColors::from_env().unwrap_or_default();
@@ -15,19 +17,59 @@
 //! let ansi_style = style.map(Style::to_ansi_term_style).unwrap_or_default();
 //! println!("{}", ansi_style.paint(path));
 //! # }
+//! # }
 //! ```
-
+//!
+//! # `no_std` support
+//! Without the default `std` feature, this crate only requires `alloc`. Parsing ANSI styles
+//! ([`Style`]) and indicators ([`Indicator`]) is still available, but [`LsColors`] and everything
+//! else that queries the file system or the environment is not.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod bsd;
+#[cfg(feature = "std")]
+mod dircolors;
+#[cfg(feature = "std")]
+mod escape;
+#[cfg(feature = "std")]
 mod fs;
 pub mod style;
-
-use std::collections::HashMap;
+#[cfg(feature = "std")]
+mod suffix;
+
+#[cfg(feature = "std")]
+use core::convert::Infallible;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
 use std::env;
-use std::ffi::OsString;
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
 use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR};
-
-pub use crate::style::{Color, FontStyle, Style};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+pub use crate::style::{Color, FontStyle, ParseStyleError, Style, UnderlineStyle};
+#[cfg(feature = "std")]
+use crate::suffix::SuffixTrie;
+
+/// The kinds of file system entries (and other codes) that can be styled in `LS_COLORS`. Indicators
+/// are ordered by their declaration order. New indicators may be added in minor releases, see
+/// [`Indicator::all`](#method.all) to iterate over all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub enum Indicator {
     /// `no`: Normal (non-filename) text
     Normal,
@@ -100,6 +142,14 @@ pub enum Indicator {
 
     /// `cl`: Code to clear to the end of the line
     ClearLine,
+
+    /// `hi`: A file or directory with the hidden attribute (Windows only). This is not part of
+    /// GNU `LS_COLORS`.
+    Hidden,
+
+    /// `ro`: A file with the read-only attribute (Windows only). This is not part of GNU
+    /// `LS_COLORS`.
+    ReadOnly,
 }
 
 impl Indicator {
@@ -129,14 +179,209 @@ impl Indicator {
             "ec" => Some(Indicator::EndCode),
             "rs" => Some(Indicator::Reset),
             "cl" => Some(Indicator::ClearLine),
+            "hi" => Some(Indicator::Hidden),
+            "ro" => Some(Indicator::ReadOnly),
+            _ => None,
+        }
+    }
+
+    /// Whether this indicator is a raw code (`lc`, `rc` or `ec`) rather than a style.
+    #[cfg(feature = "std")]
+    fn is_code(self) -> bool {
+        matches!(
+            self,
+            Indicator::LeftCode | Indicator::RightCode | Indicator::EndCode
+        )
+    }
+
+    /// Whether this indicator is a basic file type (like `fi` or `di`), rather than a more specific
+    /// type (like `ex` or `tw`) that `ls` only uses if it has a non-empty style.
+    #[cfg(feature = "std")]
+    fn is_file_type(self) -> bool {
+        matches!(
+            self,
+            Indicator::RegularFile
+                | Indicator::Directory
+                | Indicator::SymbolicLink
+                | Indicator::FIFO
+                | Indicator::Socket
+                | Indicator::Door
+                | Indicator::BlockDevice
+                | Indicator::CharacterDevice
+        )
+    }
+
+    /// Get the two-letter code for this indicator, as used in `LS_COLORS` (e.g. `di`).
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Indicator::Normal => "no",
+            Indicator::RegularFile => "fi",
+            Indicator::Directory => "di",
+            Indicator::SymbolicLink => "ln",
+            Indicator::FIFO => "pi",
+            Indicator::Socket => "so",
+            Indicator::Door => "do",
+            Indicator::BlockDevice => "bd",
+            Indicator::CharacterDevice => "cd",
+            Indicator::OrphanedSymbolicLink => "or",
+            Indicator::Setuid => "su",
+            Indicator::Setgid => "sg",
+            Indicator::Sticky => "st",
+            Indicator::OtherWritable => "ow",
+            Indicator::StickyAndOtherWritable => "tw",
+            Indicator::ExecutableFile => "ex",
+            Indicator::MissingFile => "mi",
+            Indicator::Capabilities => "ca",
+            Indicator::MultipleHardLinks => "mh",
+            Indicator::LeftCode => "lc",
+            Indicator::RightCode => "rc",
+            Indicator::EndCode => "ec",
+            Indicator::Reset => "rs",
+            Indicator::ClearLine => "cl",
+            Indicator::Hidden => "hi",
+            Indicator::ReadOnly => "ro",
+        }
+    }
+
+    /// Get all indicators, in the order used by `dircolors` (`no`, `fi`, `rs`, `di`, ..).
+    pub fn all() -> &'static [Indicator] {
+        &INDICATORS
+    }
+}
+
+#[cfg(feature = "std")]
+impl Indicator {
+    /// The indicator whose style is used if this one has no style, like in `ls` (before falling
+    /// back to `no`).
+    fn default_fallback(self) -> Option<Indicator> {
+        match self {
+            Indicator::Setuid
+            | Indicator::Setgid
+            | Indicator::ExecutableFile
+            | Indicator::MultipleHardLinks
+            | Indicator::ReadOnly => Some(Indicator::RegularFile),
+
+            Indicator::StickyAndOtherWritable | Indicator::OtherWritable | Indicator::Sticky => {
+                Some(Indicator::Directory)
+            }
+
+            Indicator::OrphanedSymbolicLink => Some(Indicator::SymbolicLink),
+
+            Indicator::MissingFile => Some(Indicator::OrphanedSymbolicLink),
+
             _ => None,
         }
     }
+
+    /// A short human-readable description of this indicator, e.g. `directory` for `di`.
+    fn description(self) -> &'static str {
+        match self {
+            Indicator::Normal => "normal text",
+            Indicator::RegularFile => "regular file",
+            Indicator::Directory => "directory",
+            Indicator::SymbolicLink => "symbolic link",
+            Indicator::FIFO => "named pipe",
+            Indicator::Socket => "socket",
+            Indicator::Door => "door",
+            Indicator::BlockDevice => "block device",
+            Indicator::CharacterDevice => "character device",
+            Indicator::OrphanedSymbolicLink => "orphaned symbolic link",
+            Indicator::Setuid => "setuid file",
+            Indicator::Setgid => "setgid file",
+            Indicator::Sticky => "sticky directory",
+            Indicator::OtherWritable => "other-writable directory",
+            Indicator::StickyAndOtherWritable => "sticky and other-writable directory",
+            Indicator::ExecutableFile => "executable file",
+            Indicator::MissingFile => "missing file",
+            Indicator::Capabilities => "file with capabilities",
+            Indicator::MultipleHardLinks => "file with multiple hard links",
+            Indicator::LeftCode => "left code",
+            Indicator::RightCode => "right code",
+            Indicator::EndCode => "end code",
+            Indicator::Reset => "reset",
+            Indicator::ClearLine => "clear line",
+            Indicator::Hidden => "hidden file",
+            Indicator::ReadOnly => "read-only file",
+        }
+    }
+}
+
+/// A rough category for a file name suffix, based on the groups in the default `dircolors`
+/// database (e.g. `archive` for `.zip`). Unknown suffixes are just described as `file`.
+#[cfg(feature = "std")]
+fn suffix_category(suffix: &str) -> &'static str {
+    const ARCHIVES: &[&str] = &[
+        "tar", "tgz", "arc", "arj", "taz", "lha", "lz4", "lzh", "lzma", "tlz", "txz", "tzo", "t7z",
+        "zip", "z", "dz", "gz", "lrz", "lz", "lzo", "xz", "zst", "tzst", "bz2", "bz", "tbz",
+        "tbz2", "tz", "deb", "rpm", "jar", "war", "ear", "sar", "rar", "alz", "ace", "zoo", "cpio",
+        "7z", "rz", "cab", "wim", "swm", "dwm", "esd",
+    ];
+    const IMAGES: &[&str] = &[
+        "jpg", "jpeg", "mjpg", "mjpeg", "gif", "bmp", "pbm", "pgm", "ppm", "tga", "xbm", "xpm",
+        "tif", "tiff", "png", "svg", "svgz", "mng", "pcx", "xcf", "xwd", "yuv", "cgm", "emf",
+    ];
+    const VIDEOS: &[&str] = &[
+        "mov", "mpg", "mpeg", "m2v", "mkv", "webm", "ogm", "mp4", "m4v", "mp4v", "vob", "qt",
+        "nuv", "wmv", "asf", "rm", "rmvb", "flc", "avi", "fli", "flv", "gl", "dl", "ogv", "ogx",
+    ];
+    const AUDIO: &[&str] = &[
+        "aac", "au", "flac", "m4a", "mid", "midi", "mka", "mp3", "mpc", "ogg", "ra", "wav", "oga",
+        "opus", "spx", "xspf",
+    ];
+
+    let extension = match suffix.strip_prefix('.') {
+        Some(extension) => extension.to_ascii_lowercase(),
+        None => return "file",
+    };
+    let extension = extension.as_str();
+    if ARCHIVES.contains(&extension) {
+        "archive"
+    } else if IMAGES.contains(&extension) {
+        "image"
+    } else if VIDEOS.contains(&extension) {
+        "video"
+    } else if AUDIO.contains(&extension) {
+        "audio"
+    } else {
+        "file"
+    }
 }
 
+/// All indicators, in the order used by `dircolors`.
+const INDICATORS: [Indicator; 26] = [
+    Indicator::Normal,
+    Indicator::RegularFile,
+    Indicator::Reset,
+    Indicator::Directory,
+    Indicator::SymbolicLink,
+    Indicator::MultipleHardLinks,
+    Indicator::FIFO,
+    Indicator::Socket,
+    Indicator::Door,
+    Indicator::BlockDevice,
+    Indicator::CharacterDevice,
+    Indicator::OrphanedSymbolicLink,
+    Indicator::MissingFile,
+    Indicator::Setuid,
+    Indicator::Setgid,
+    Indicator::Capabilities,
+    Indicator::StickyAndOtherWritable,
+    Indicator::OtherWritable,
+    Indicator::Sticky,
+    Indicator::ExecutableFile,
+    Indicator::LeftCode,
+    Indicator::RightCode,
+    Indicator::EndCode,
+    Indicator::ClearLine,
+    Indicator::Hidden,
+    Indicator::ReadOnly,
+];
+
+#[cfg(feature = "std")]
 type FileNameSuffix = String;
 
 /// Iterator over the path components with their respective style.
+#[cfg(feature = "std")]
 pub struct StyledComponents<'a> {
     /// Reference to the underlying LsColors object
     lscolors: &'a LsColors,
@@ -146,26 +391,42 @@ pub struct StyledComponents<'a> {
 
     /// Underlying iterator over the path components
     components: std::iter::Peekable<std::path::Components<'a>>,
+
+    /// Whether the original path ends with a separator (like `foo/bar/`)
+    trailing_separator: bool,
+
+    /// The separator that is appended to the components
+    separator: char,
 }
 
+#[cfg(feature = "std")]
 impl<'a> Iterator for StyledComponents<'a> {
     type Item = (OsString, Option<&'a Style>);
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(component) = self.components.next() {
-            let mut component_str = component.as_os_str().to_os_string();
-
-            self.component_path.push(&component_str);
-            let style = self.lscolors.style_for_path(&self.component_path);
+            self.component_path.push(component.as_os_str());
+            let mut component_str = match component {
+                Component::RootDir => OsString::from(self.separator.to_string()),
+                _ => component.as_os_str().to_os_string(),
+            };
+
+            let style = match component {
+                // Like `ls`, treat `.` and `..` as directories without looking at the file system
+                Component::CurDir | Component::ParentDir => {
+                    self.lscolors.style_for_indicator(Indicator::Directory)
+                }
+                _ => self.lscolors.style_for_path(&self.component_path),
+            };
 
-            if self.components.peek().is_some() {
+            if self.components.peek().is_some() || self.trailing_separator {
                 match component {
                     // Prefix needs no separator, as it is always followed by RootDir.
                     // RootDir is already a separator.
                     Component::Prefix(_) | Component::RootDir => {}
                     // Everything else uses a separator that is painted the same way as the component.
                     Component::CurDir | Component::ParentDir | Component::Normal(_) => {
-                        component_str.push(MAIN_SEPARATOR.to_string());
+                        component_str.push(self.separator.to_string());
                     }
                 }
             }
@@ -177,296 +438,2462 @@ impl<'a> Iterator for StyledComponents<'a> {
     }
 }
 
-const LS_COLORS_DEFAULT: &str = "rs=0:di=01;34:ln=01;36:mh=00:pi=40;33:so=01;35:do=01;35:bd=40;33;01:cd=40;33;01:or=40;31;01:mi=00:su=37;41:sg=30;43:ca=30;41:tw=30;42:ow=34;42:st=37;44:ex=01;32:*.tar=01;31:*.tgz=01;31:*.arc=01;31:*.arj=01;31:*.taz=01;31:*.lha=01;31:*.lz4=01;31:*.lzh=01;31:*.lzma=01;31:*.tlz=01;31:*.txz=01;31:*.tzo=01;31:*.t7z=01;31:*.zip=01;31:*.z=01;31:*.dz=01;31:*.gz=01;31:*.lrz=01;31:*.lz=01;31:*.lzo=01;31:*.xz=01;31:*.zst=01;31:*.tzst=01;31:*.bz2=01;31:*.bz=01;31:*.tbz=01;31:*.tbz2=01;31:*.tz=01;31:*.deb=01;31:*.rpm=01;31:*.jar=01;31:*.war=01;31:*.ear=01;31:*.sar=01;31:*.rar=01;31:*.alz=01;31:*.ace=01;31:*.zoo=01;31:*.cpio=01;31:*.7z=01;31:*.rz=01;31:*.cab=01;31:*.wim=01;31:*.swm=01;31:*.dwm=01;31:*.esd=01;31:*.jpg=01;35:*.jpeg=01;35:*.mjpg=01;35:*.mjpeg=01;35:*.gif=01;35:*.bmp=01;35:*.pbm=01;35:*.pgm=01;35:*.ppm=01;35:*.tga=01;35:*.xbm=01;35:*.xpm=01;35:*.tif=01;35:*.tiff=01;35:*.png=01;35:*.svg=01;35:*.svgz=01;35:*.mng=01;35:*.pcx=01;35:*.mov=01;35:*.mpg=01;35:*.mpeg=01;35:*.m2v=01;35:*.mkv=01;35:*.webm=01;35:*.ogm=01;35:*.mp4=01;35:*.m4v=01;35:*.mp4v=01;35:*.vob=01;35:*.qt=01;35:*.nuv=01;35:*.wmv=01;35:*.asf=01;35:*.rm=01;35:*.rmvb=01;35:*.flc=01;35:*.avi=01;35:*.fli=01;35:*.flv=01;35:*.gl=01;35:*.dl=01;35:*.xcf=01;35:*.xwd=01;35:*.yuv=01;35:*.cgm=01;35:*.emf=01;35:*.ogv=01;35:*.ogx=01;35:*.aac=00;36:*.au=00;36:*.flac=00;36:*.m4a=00;36:*.mid=00;36:*.midi=00;36:*.mka=00;36:*.mp3=00;36:*.mpc=00;36:*.ogg=00;36:*.ra=00;36:*.wav=00;36:*.oga=00;36:*.opus=00;36:*.spx=00;36:*.xspf=00;36:";
+/// A cache for the file name suffix lookups of
+/// [`LsColors::style_for_path_cached`](struct.LsColors.html#method.style_for_path_cached), keyed
+/// by file extension. A cache must only be used with a single `LsColors` instance, and it has to
+/// be cleared if that instance is modified.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct StyleCache {
+    extensions: HashMap<Vec<u8>, CachedSuffix>,
+}
+
+/// The cached suffix rule for an extension.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+enum CachedSuffix {
+    /// The index of the matching rule (if any), which only depends on the extension
+    Rule(Option<usize>),
+    /// There are longer rules ending in this extension (like `*.tar.gz` for `.gz`), so the
+    /// whole file name has to be considered
+    Ambiguous,
+}
+
+#[cfg(feature = "std")]
+impl StyleCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        StyleCache::default()
+    }
+
+    /// Remove all cached entries, e.g. after modifying the `LsColors` instance.
+    pub fn clear(&mut self) {
+        self.extensions.clear();
+    }
+}
+
+/// The type of a file that is not on the local file system, e.g. from a remote listing. See
+/// [`LsColors::style_for_type`](struct.LsColors.html#method.style_for_type).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileTypeHint {
+    /// A regular file
+    File,
+
+    /// An executable regular file
+    ExecutableFile,
+
+    /// A directory
+    Directory,
+
+    /// A symbolic link
+    SymbolicLink,
+
+    /// A symbolic link whose target does not exist
+    OrphanedSymbolicLink,
+
+    /// A named pipe (FIFO)
+    FIFO,
+
+    /// A socket
+    Socket,
+
+    /// A block device
+    BlockDevice,
+
+    /// A character device
+    CharacterDevice,
+}
+
+#[cfg(feature = "std")]
+impl FileTypeHint {
+    /// Get the indicator for files of this type.
+    fn indicator(self) -> Indicator {
+        match self {
+            FileTypeHint::File => Indicator::RegularFile,
+            FileTypeHint::ExecutableFile => Indicator::ExecutableFile,
+            FileTypeHint::Directory => Indicator::Directory,
+            FileTypeHint::SymbolicLink => Indicator::SymbolicLink,
+            FileTypeHint::OrphanedSymbolicLink => Indicator::OrphanedSymbolicLink,
+            FileTypeHint::FIFO => Indicator::FIFO,
+            FileTypeHint::Socket => Indicator::Socket,
+            FileTypeHint::BlockDevice => Indicator::BlockDevice,
+            FileTypeHint::CharacterDevice => Indicator::CharacterDevice,
+        }
+    }
+}
+
+/// The reason why a style was chosen for a path, as returned by
+/// [`LsColors::style_for_path_with_reason`](struct.LsColors.html#method.style_for_path_with_reason).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MatchReason<'a> {
+    /// The style of an indicator was used. This is the indicator whose style was actually found,
+    /// after the fallback logic (e.g. `Normal` for a file without a specific style).
+    Indicator(Indicator),
+
+    /// The style of a file name suffix rule (like `".png"`) was used.
+    Suffix(&'a str),
+}
+
+/// A single rule of an [`LsColors`](struct.LsColors.html) instance, as returned by
+/// [`LsColors::iter`](struct.LsColors.html#method.iter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Rule<'a> {
+    /// The style of an indicator, like `di=01;34`
+    Indicator(Indicator, &'a Style),
+
+    /// The style of a file name suffix, like `*.png=01;35`
+    Suffix(&'a str, &'a Style),
+}
+
+/// Iterator over all rules of an [`LsColors`](struct.LsColors.html) instance, see
+/// [`LsColors::iter`](struct.LsColors.html#method.iter).
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct Rules<'a> {
+    lscolors: &'a LsColors,
+    indicators: std::slice::Iter<'a, Indicator>,
+    suffixes: std::slice::Iter<'a, (FileNameSuffix, Arc<Style>)>,
+}
+
+#[cfg(feature = "std")]
+impl<'a> Iterator for Rules<'a> {
+    type Item = Rule<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for indicator in self.indicators.by_ref() {
+            if let Some(style) = self.lscolors.indicator_mapping.get(indicator) {
+                return Some(Rule::Indicator(*indicator, style));
+            }
+        }
+
+        self.suffixes
+            .next()
+            .map(|(suffix, style)| Rule::Suffix(suffix, style))
+    }
+}
+
+/// The differences between two [`LsColors`](struct.LsColors.html) instances, as returned by
+/// [`LsColors::diff`](struct.LsColors.html#method.diff). Indicators are listed in the order used
+/// by `dircolors`, and suffixes in the order they were declared.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LsColorsDiff<'a> {
+    /// Rules that only exist in the other instance
+    pub added: Vec<Rule<'a>>,
+
+    /// Rules that only exist in this instance
+    pub removed: Vec<Rule<'a>>,
+
+    /// Rules that exist in both instances with different styles, as pairs of the rule in this
+    /// instance and the rule in the other one
+    pub changed: Vec<(Rule<'a>, Rule<'a>)>,
+}
+
+#[cfg(feature = "std")]
+impl<'a> LsColorsDiff<'a> {
+    /// Whether both instances have the same indicator and suffix styles.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Compare two lists of rules with unique keys, according to `eq`.
+    fn compare<K: Copy>(
+        &mut self,
+        old: &[(K, &'a Style)],
+        new: &[(K, &'a Style)],
+        eq: impl Fn(K, K) -> bool,
+        rule: fn(K, &'a Style) -> Rule<'a>,
+    ) {
+        let find = |rules: &[(K, &'a Style)], key: K| {
+            rules
+                .iter()
+                .find(|(other, _)| eq(*other, key))
+                .map(|(_, style)| *style)
+        };
+
+        for &(key, style) in old {
+            match find(new, key) {
+                None => self.removed.push(rule(key, style)),
+                Some(other) if other != style => {
+                    self.changed.push((rule(key, style), rule(key, other)))
+                }
+                Some(_) => {}
+            }
+        }
+        for &(key, style) in new {
+            if find(old, key).is_none() {
+                self.added.push(rule(key, style));
+            }
+        }
+    }
+}
+
+/// A piece of text together with its (optional) style. The `Display` implementation wraps the
+/// text in ANSI escape sequences. See [`LsColors::paint`](struct.LsColors.html#method.paint).
+#[derive(Debug, Clone, Copy)]
+pub struct Painted<'a> {
+    text: &'a str,
+    style: Option<&'a Style>,
+
+    /// The `lc`, `rc` and (optional) `ec` codes
+    left_code: &'a str,
+    right_code: &'a str,
+    end_code: Option<&'a str>,
+}
+
+impl<'a> fmt::Display for Painted<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.style {
+            Some(style) => {
+                write!(
+                    f,
+                    "{}{}{}{}",
+                    self.left_code,
+                    style.to_ansi_sequence(),
+                    self.right_code,
+                    self.text
+                )?;
+                match self.end_code {
+                    Some(end_code) => f.write_str(end_code),
+                    None => write!(f, "{}0{}", self.left_code, self.right_code),
+                }
+            }
+            None => f.write_str(self.text),
+        }
+    }
+}
+
+/// The default `LS_COLORS` value, as used by the `Default` implementation of
+/// [`LsColors`](struct.LsColors.html). See `man dircolors` for more information.
+///
+/// ```
+/// # #[cfg(feature = "std")]
+/// # {
+/// use lscolors::{LsColors, DEFAULT_LS_COLORS};
+///
+/// let lscolors = LsColors::from_string(DEFAULT_LS_COLORS);
+/// assert_eq!(
+///     LsColors::default().style_for_path("archive.zip"),
+///     lscolors.style_for_path("archive.zip")
+/// );
+/// # }
+/// ```
+pub const DEFAULT_LS_COLORS: &str = "rs=0:di=01;34:ln=01;36:mh=00:pi=40;33:so=01;35:do=01;35:bd=40;33;01:cd=40;33;01:or=40;31;01:mi=00:su=37;41:sg=30;43:ca=30;41:tw=30;42:ow=34;42:st=37;44:ex=01;32:*.tar=01;31:*.tgz=01;31:*.arc=01;31:*.arj=01;31:*.taz=01;31:*.lha=01;31:*.lz4=01;31:*.lzh=01;31:*.lzma=01;31:*.tlz=01;31:*.txz=01;31:*.tzo=01;31:*.t7z=01;31:*.zip=01;31:*.z=01;31:*.dz=01;31:*.gz=01;31:*.lrz=01;31:*.lz=01;31:*.lzo=01;31:*.xz=01;31:*.zst=01;31:*.tzst=01;31:*.bz2=01;31:*.bz=01;31:*.tbz=01;31:*.tbz2=01;31:*.tz=01;31:*.deb=01;31:*.rpm=01;31:*.jar=01;31:*.war=01;31:*.ear=01;31:*.sar=01;31:*.rar=01;31:*.alz=01;31:*.ace=01;31:*.zoo=01;31:*.cpio=01;31:*.7z=01;31:*.rz=01;31:*.cab=01;31:*.wim=01;31:*.swm=01;31:*.dwm=01;31:*.esd=01;31:*.jpg=01;35:*.jpeg=01;35:*.mjpg=01;35:*.mjpeg=01;35:*.gif=01;35:*.bmp=01;35:*.pbm=01;35:*.pgm=01;35:*.ppm=01;35:*.tga=01;35:*.xbm=01;35:*.xpm=01;35:*.tif=01;35:*.tiff=01;35:*.png=01;35:*.svg=01;35:*.svgz=01;35:*.mng=01;35:*.pcx=01;35:*.mov=01;35:*.mpg=01;35:*.mpeg=01;35:*.m2v=01;35:*.mkv=01;35:*.webm=01;35:*.ogm=01;35:*.mp4=01;35:*.m4v=01;35:*.mp4v=01;35:*.vob=01;35:*.qt=01;35:*.nuv=01;35:*.wmv=01;35:*.asf=01;35:*.rm=01;35:*.rmvb=01;35:*.flc=01;35:*.avi=01;35:*.fli=01;35:*.flv=01;35:*.gl=01;35:*.dl=01;35:*.xcf=01;35:*.xwd=01;35:*.yuv=01;35:*.cgm=01;35:*.emf=01;35:*.ogv=01;35:*.ogx=01;35:*.aac=00;36:*.au=00;36:*.flac=00;36:*.m4a=00;36:*.mid=00;36:*.midi=00;36:*.mka=00;36:*.mp3=00;36:*.mpc=00;36:*.ogg=00;36:*.ra=00;36:*.wav=00;36:*.oga=00;36:*.opus=00;36:*.spx=00;36:*.xspf=00;36:";
+
+/// Whether two file name suffixes are the same, optionally ignoring (ASCII) case like the
+/// suffix trie.
+#[cfg(feature = "std")]
+fn suffixes_eq(a: &str, b: &str, case_sensitive: bool) -> bool {
+    if case_sensitive {
+        a == b
+    } else {
+        a.eq_ignore_ascii_case(b)
+    }
+}
+
+/// Decide whether to use colors, based on the values of `NO_COLOR` and `CLICOLOR_FORCE`.
+#[cfg(feature = "std")]
+fn colors_enabled(no_color: Option<&OsStr>, clicolor_force: Option<&OsStr>) -> bool {
+    let forced = matches!(clicolor_force, Some(force) if !force.is_empty() && force != "0");
+    let disabled = matches!(no_color, Some(no_color) if !no_color.is_empty());
+
+    forced || !disabled
+}
+
+/// Decide whether the terminal supports 24-bit colors, based on the value of `COLORTERM`.
+#[cfg(feature = "std")]
+fn supports_truecolor(colorterm: Option<&OsStr>) -> bool {
+    matches!(colorterm, Some(colorterm) if colorterm == "truecolor" || colorterm == "24bit")
+}
+
+/// Replace the 24-bit colors of a style by the closest 8-bit colors, if it has any.
+#[cfg(feature = "std")]
+fn downgrade_style(style: &Style) -> Option<Style> {
+    let is_rgb = |color: &Option<Color>| matches!(color, Some(Color::RGB(..)));
+    if !is_rgb(&style.foreground) && !is_rgb(&style.background) && !is_rgb(&style.underline_color) {
+        return None;
+    }
+
+    let to_fixed = |color: &Option<Color>| color.as_ref().map(Color::to_fixed);
+    let mut style = style.clone();
+    style.foreground = to_fixed(&style.foreground);
+    style.background = to_fixed(&style.background);
+    style.underline_color = to_fixed(&style.underline_color);
+    Some(style)
+}
 
 /// Holds information about how different file system entries should be colorized / styled.
+#[cfg(feature = "std")]
 #[derive(Debug, Clone)]
 pub struct LsColors {
-    indicator_mapping: HashMap<Indicator, Style>,
+    // Styles are shared via `Arc`, so that cloning an `LsColors` instance is cheap.
+    indicator_mapping: HashMap<Indicator, Arc<Style>>,
 
     // Note: you might expect to see a `HashMap` for `suffix_mapping` as well, but we need to
     // preserve the exact order of the mapping in order to be consistent with `ls`.
-    suffix_mapping: Vec<(FileNameSuffix, Style)>,
+    suffix_mapping: Vec<(FileNameSuffix, Arc<Style>)>,
+
+    /// Index into `suffix_mapping` for fast lookups
+    suffix_trie: SuffixTrie,
+
+    /// Whether file name suffixes are matched case-sensitively
+    case_sensitive: bool,
+
+    /// Whether the longest matching suffix wins, instead of the last declared one
+    longest_match: bool,
+
+    /// Whether file name suffixes are also matched for (plain) directories
+    suffixes_on_directories: bool,
+
+    /// Whether file name suffixes take precedence over `mh` for files with multiple hard links
+    suffixes_on_hard_links: bool,
+
+    /// Whether symbolic links are styled like their target (`ln=target`)
+    symlink_as_target: bool,
+
+    /// The raw (unescaped) strings for the `lc`, `rc` and `ec` indicators
+    code_mapping: HashMap<Indicator, String>,
+
+    /// Styles for unrecognized keys, e.g. the extra codes used by `eza` (`uu`, `sn`, ..)
+    extra_mapping: HashMap<String, Arc<Style>>,
+
+    /// Custom keys registered by the application, which fall back to `no` like indicators
+    custom_indicators: HashSet<String>,
+
+    /// Custom fallbacks for indicators without a style, overriding the ones from `ls`
+    fallback_mapping: HashMap<Indicator, Indicator>,
 }
 
+#[cfg(feature = "std")]
 impl Default for LsColors {
     /// Constructs a default `LsColors` instance with some default styles. See `man dircolors` for
     /// information about the default styles and colors.
     fn default() -> Self {
         let mut lscolors = LsColors::empty();
-        lscolors.add_from_string(LS_COLORS_DEFAULT);
+        lscolors.add_from_string(DEFAULT_LS_COLORS);
         lscolors
     }
 }
 
+#[cfg(feature = "std")]
+impl<'a> IntoIterator for &'a LsColors {
+    type Item = Rule<'a>;
+    type IntoIter = Rules<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromStr for LsColors {
+    type Err = Infallible;
+
+    /// Parse an `LS_COLORS` value on top of the default styles, see
+    /// [`from_string`](struct.LsColors.html#method.from_string). This never fails, since invalid
+    /// entries are ignored.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(LsColors::from_string(input))
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for LsColors {
+    /// Formats this instance as an `LS_COLORS` value that can be parsed again via
+    /// [`from_string`](struct.LsColors.html#method.from_string).
+    ///
+    /// Like in `ls`, a reset like `ex=0` only has an effect for suffixes and the basic file types
+    /// (like `fi` or `di`), while other indicators are treated as unset. So these indicators are
+    /// left out if they have the default style, and they fall back to the more general indicators
+    /// when the value is parsed again.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for indicator in INDICATORS.iter() {
+            if let Some(code) = self.code_mapping.get(indicator) {
+                write!(f, "{}={}:", indicator.to_str(), escape::escape(code))?;
+            } else if let Some(style) = self.indicator_mapping.get(indicator) {
+                if !style.is_default() || indicator.is_file_type() {
+                    write!(f, "{}={}:", indicator.to_str(), style.to_ansi_sequence())?;
+                }
+            } else if *indicator == Indicator::SymbolicLink && self.symlink_as_target {
+                write!(f, "ln=target:")?;
+            }
+        }
+
+        for (suffix, style) in &self.suffix_mapping {
+            write!(
+                f,
+                "*{}={}:",
+                escape::escape(suffix),
+                style.to_ansi_sequence()
+            )?;
+        }
+
+        let mut extras: Vec<_> = self.extra_mapping.iter().collect();
+        extras.sort_by_key(|(key, _)| *key);
+        for (key, style) in extras {
+            write!(f, "{}={}:", escape::escape(key), style.to_ansi_sequence())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
 impl LsColors {
     /// Construct an empty [`LsColors`](struct.LsColors.html) instance with no pre-defined styles.
     pub fn empty() -> Self {
         LsColors {
             indicator_mapping: HashMap::new(),
             suffix_mapping: vec![],
+            suffix_trie: SuffixTrie::new(false),
+            case_sensitive: false,
+            longest_match: false,
+            suffixes_on_directories: false,
+            suffixes_on_hard_links: false,
+            symlink_as_target: false,
+            code_mapping: HashMap::new(),
+            extra_mapping: HashMap::new(),
+            custom_indicators: HashSet::new(),
+            fallback_mapping: HashMap::new(),
+        }
+    }
+
+    /// Enable or disable case-sensitive matching of file name suffixes. By default, suffixes are
+    /// matched case-insensitively (like GNU `ls`), so `*.jpg` also matches `photo.JPG`.
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self.rebuild_suffix_trie();
+        self
+    }
+
+    /// Enable or disable longest-match mode for file name suffixes. By default (like `ls`), the
+    /// last declared suffix that matches a file name wins. So if `LS_COLORS` contains `*.tar.gz`
+    /// before `*.gz`, the `*.gz` style is used for `x.tar.gz`. In longest-match mode, the longest
+    /// matching suffix wins instead, so `*.tar.gz` is used regardless of the order.
+    pub fn with_longest_match(mut self, longest_match: bool) -> Self {
+        self.longest_match = longest_match;
+        self
+    }
+
+    /// Enable or disable matching file name suffixes for directories. By default (like `ls`),
+    /// suffix rules only apply to regular files. If enabled, they also apply to directories
+    /// without a more specific style (`tw`, `ow` or `st`), so e.g. a directory named `backup.zip`
+    /// gets the `*.zip` style.
+    pub fn with_suffixes_on_directories(mut self, suffixes_on_directories: bool) -> Self {
+        self.suffixes_on_directories = suffixes_on_directories;
+        self
+    }
+
+    /// Enable or disable matching file name suffixes for files with multiple hard links. By
+    /// default (like `ls`), the `mh` style takes precedence over suffix rules. If enabled, suffix
+    /// rules are matched first, so e.g. a hard-linked `file.zip` keeps the `*.zip` style, and `mh`
+    /// only applies to files without a matching suffix.
+    pub fn with_suffixes_on_hard_links(mut self, suffixes_on_hard_links: bool) -> Self {
+        self.suffixes_on_hard_links = suffixes_on_hard_links;
+        self
+    }
+
+    /// Whether file name suffixes are matched for files with the given indicator.
+    fn matches_suffixes(&self, indicator: Indicator) -> bool {
+        match indicator {
+            Indicator::RegularFile => true,
+            Indicator::MultipleHardLinks => self.suffixes_on_hard_links,
+            Indicator::Directory => self.suffixes_on_directories,
+            _ => false,
+        }
+    }
+
+    /// Find the index of the suffix rule that matches a file name.
+    fn find_suffix(&self, filename: &[u8]) -> Option<usize> {
+        if self.longest_match {
+            self.suffix_trie.find_longest(filename)
+        } else {
+            self.suffix_trie.find(filename)
+        }
+    }
+
+    fn rebuild_suffix_trie(&mut self) {
+        self.suffix_trie = SuffixTrie::new(self.case_sensitive);
+        for (i, (suffix, _)) in self.suffix_mapping.iter().enumerate() {
+            self.suffix_trie.insert(suffix.as_bytes(), i);
         }
     }
 
     /// Creates a new [`LsColors`](struct.LsColors.html) instance from the `LS_COLORS` environment
     /// variable. The basis for this is a default style as constructed via the `Default`
-    /// implementation.
+    /// implementation, just like for [`from_string`](#method.from_string).
+    ///
+    /// Returns `None` if `LS_COLORS` is not set at all, or if it is not valid UTF-8 (see
+    /// [`from_env_lossy`](#method.from_env_lossy)). An empty value results in the default styles.
     pub fn from_env() -> Option<Self> {
-        env::var("LS_COLORS")
-            .ok()
-            .as_ref()
-            .map(|s| Self::from_string(s))
+        env::var("LS_COLORS").ok().map(|s| Self::from_string(&s))
+    }
+
+    /// Like [`from_env`](#method.from_env), but converts a value that is not valid UTF-8 lossily,
+    /// i.e. invalid sequences become U+FFFD replacement characters (which may end up in suffixes).
+    /// Returns `None` only if `LS_COLORS` is not set at all.
+    pub fn from_env_lossy() -> Option<Self> {
+        env::var_os("LS_COLORS").map(|s| Self::from_string(&s.to_string_lossy()))
+    }
+
+    /// Like [`from_env`](#method.from_env), but falls back to the default styles if `LS_COLORS`
+    /// is not set (or not valid UTF-8).
+    pub fn from_env_or_default() -> Self {
+        Self::from_env().unwrap_or_default()
+    }
+
+    /// Like [`from_env`](#method.from_env), but also honors the `NO_COLOR` and `CLICOLOR_FORCE`
+    /// conventions. Returns `None` if colors should be disabled, i.e. if `NO_COLOR` is set to a
+    /// non-empty value. Setting `CLICOLOR_FORCE` to a value other than `0` takes precedence over
+    /// `NO_COLOR`. If colors are enabled but `LS_COLORS` is not set, the default styles are used.
+    pub fn from_env_with_overrides() -> Option<Self> {
+        let no_color = env::var_os("NO_COLOR");
+        let clicolor_force = env::var_os("CLICOLOR_FORCE");
+
+        if colors_enabled(no_color.as_deref(), clicolor_force.as_deref()) {
+            Some(Self::from_env().unwrap_or_default())
+        } else {
+            None
+        }
     }
 
-    /// Creates a new [`LsColors`](struct.LsColors.html) instance from the given string.
+    /// Creates a new [`LsColors`](struct.LsColors.html) instance from the given string. Invalid
+    /// entries, empty entries (`::`) and entries with an empty key (`=31`) are ignored. Like in
+    /// `ls`, an empty suffix (`*=31`) matches every file name.
     pub fn from_string(input: &str) -> Self {
         let mut lscolors = LsColors::default();
         lscolors.add_from_string(input);
         lscolors
     }
 
-    fn add_from_string(&mut self, input: &str) {
-        for entry in input.split(':') {
-            let parts: Vec<_> = entry.split('=').collect();
-
-            if let Some([entry, ansi_style]) = parts.get(0..2) {
-                let style = Style::from_ansi_sequence(ansi_style);
-                if let Some(suffix) = entry.strip_prefix('*') {
-                    if let Some(style) = style {
-                        self.suffix_mapping
-                            .push((suffix.to_string().to_ascii_lowercase(), style));
-                    }
-                } else if let Some(indicator) = Indicator::from(entry) {
-                    if let Some(style) = style {
-                        self.indicator_mapping.insert(indicator, style);
-                    } else {
-                        self.indicator_mapping.remove(&indicator);
-                    }
-                }
-            }
-        }
+    /// Creates a new [`LsColors`](struct.LsColors.html) instance from a raw `LS_COLORS` value
+    /// (e.g. stored in a file), on top of the default styles. Surrounding whitespace, like a
+    /// trailing newline, is ignored.
+    pub fn from_reader<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        Ok(Self::from_string(input.trim()))
     }
 
-    /// Get the ANSI style for a given path.
-    ///
-    /// *Note:* this function calls `Path::symlink_metadata` internally. If you already happen to
-    /// have the `Metadata` available, use [`style_for_path_with_metadata`](#method.style_for_path_with_metadata).
-    pub fn style_for_path<P: AsRef<Path>>(&self, path: P) -> Option<&Style> {
-        let metadata = path.as_ref().symlink_metadata().ok();
-        self.style_for_path_with_metadata(path, metadata.as_ref())
+    /// Creates a new [`LsColors`](struct.LsColors.html) instance from a `dircolors` database
+    /// (the format of `~/.dir_colors` or `dircolors --print-database`), with lines like
+    /// `DIR 01;34` or `.tar 01;31`. Unlike [`from_string`](#method.from_string), this does not
+    /// start from the default styles, since the database is a complete configuration.
+    pub fn from_dircolors_reader<R: io::BufRead>(reader: R) -> io::Result<Self> {
+        let mut lscolors = LsColors::empty();
+        for (key, value) in dircolors::parse(reader)? {
+            lscolors.add_entry(&key, &value);
+        }
+        Ok(lscolors)
     }
 
-    /// Check if an indicator has an associated color.
-    fn has_color_for(&self, indicator: Indicator) -> bool {
-        self.indicator_mapping.contains_key(&indicator)
+    /// Creates a new [`LsColors`](struct.LsColors.html) instance from an `LSCOLORS` value, as used
+    /// by the BSD and macOS versions of `ls` (e.g. `exfxcxdxbxegedabagacad`). Each of the 11
+    /// positions is a foreground and a background letter, for `di`, `ln`, `so`, `pi`, `ex`, `bd`,
+    /// `cd`, `su`, `sg`, `tw` and `ow`. Missing positions are left unset and extra characters are
+    /// ignored. Like [`from_dircolors_reader`](#method.from_dircolors_reader), this does not start
+    /// from the default styles.
+    pub fn from_bsd_string(input: &str) -> Self {
+        let mut lscolors = LsColors::empty();
+        for (key, value) in bsd::parse(input) {
+            lscolors.add_entry(key, &value);
+        }
+        lscolors
     }
 
-    /// Get the indicator type for a path with corresponding metadata.
-    fn indicator_for(&self, path: &Path, metadata: Option<&std::fs::Metadata>) -> Indicator {
-        if let Some(metadata) = metadata {
-            let file_type = metadata.file_type();
-
-            if file_type.is_file() {
-                let mode = crate::fs::mode(metadata);
-                let nlink = crate::fs::nlink(metadata);
-
-                if self.has_color_for(Indicator::Setuid) && mode & 0o4000 != 0 {
-                    Indicator::Setuid
-                } else if self.has_color_for(Indicator::Setgid) && mode & 0o2000 != 0 {
-                    Indicator::Setgid
-                } else if self.has_color_for(Indicator::ExecutableFile) && mode & 0o0111 != 0 {
-                    Indicator::ExecutableFile
-                } else if self.has_color_for(Indicator::MultipleHardLinks) && nlink > 1 {
-                    Indicator::MultipleHardLinks
-                } else {
-                    Indicator::RegularFile
-                }
-            } else if file_type.is_dir() {
-                let mode = crate::fs::mode(metadata);
-
-                if self.has_color_for(Indicator::StickyAndOtherWritable) && mode & 0o1002 == 0o1002
-                {
-                    Indicator::StickyAndOtherWritable
-                } else if self.has_color_for(Indicator::OtherWritable) && mode & 0o0002 != 0 {
-                    Indicator::OtherWritable
-                } else if self.has_color_for(Indicator::Sticky) && mode & 0o1000 != 0 {
-                    Indicator::Sticky
-                } else {
-                    Indicator::Directory
-                }
-            } else if file_type.is_symlink() {
-                // This works because `Path::exists` traverses symlinks.
-                if self.has_color_for(Indicator::OrphanedSymbolicLink) && !path.exists() {
-                    return Indicator::OrphanedSymbolicLink;
-                }
-
-                Indicator::SymbolicLink
-            } else {
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::FileTypeExt;
+    /// Creates a new [`LsColors`](struct.LsColors.html) instance from `(key, value)` pairs in the
+    /// `LS_COLORS` format, like `("di", "01;34")` or `("*.tar", "01;31")`, e.g. from a table in a
+    /// configuration file. Like [`from_string`](#method.from_string), this starts from the default
+    /// styles.
+    pub fn from_pairs<I, K, V>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut lscolors = LsColors::default();
+        for (key, value) in pairs {
+            lscolors.add_entry(key.as_ref(), value.as_ref());
+        }
+        lscolors
+    }
 
-                    if file_type.is_fifo() {
-                        return Indicator::FIFO;
-                    }
-                    if file_type.is_socket() {
-                        return Indicator::Socket;
-                    }
-                    if file_type.is_block_device() {
-                        return Indicator::BlockDevice;
-                    }
-                    if file_type.is_char_device() {
-                        return Indicator::CharacterDevice;
-                    }
-                }
+    /// Parse an `LS_COLORS` value on top of this instance, without starting from the default
+    /// styles like [`from_string`](#method.from_string). Entries in `input` take precedence over
+    /// existing ones, so multiple sources can be layered explicitly, e.g. a configuration file
+    /// and then the `LS_COLORS` environment variable.
+    pub fn extend_from_string(&mut self, input: &str) {
+        self.add_from_string(input);
+    }
 
-                // Treat files of unknown type as errors
-                Indicator::MissingFile
+    fn add_from_string(&mut self, input: &str) {
+        // Like `ls`, separators can be escaped (`*.a\:b=01;31`)
+        for entry in escape::split_unescaped(input, ':') {
+            // The key ends at the first `=`, the rest is the value
+            if let Some((entry, ansi_style)) = escape::split_once_unescaped(entry, '=') {
+                self.add_entry(entry, ansi_style);
             }
-        } else {
-            // Default to a regular file, so we still try the suffix map when no metadata is available
-            Indicator::RegularFile
         }
     }
 
-    /// Get the ANSI style for a path, given the corresponding `Metadata` struct.
-    ///
-    /// *Note:* The `Metadata` struct must have been acquired via `Path::symlink_metadata` in
-    /// order to colorize symbolic links correctly.
-    pub fn style_for_path_with_metadata<P: AsRef<Path>>(
-        &self,
-        path: P,
-        metadata: Option<&std::fs::Metadata>,
-    ) -> Option<&Style> {
-        let indicator = self.indicator_for(path.as_ref(), metadata);
+    /// Add a single `key=value` entry from `LS_COLORS`, like `di=01;34` or `*.tar=01;31`. Both
+    /// the key and the value may contain escape sequences like `\:` or `\033`.
+    fn add_entry(&mut self, entry: &str, ansi_style: &str) {
+        let entry = &*escape::unescape(entry);
+        let ansi_style = &*escape::unescape(ansi_style);
 
-        if indicator == Indicator::RegularFile {
-            // Note: using '.to_str()' here means that filename
-            // matching will not work with invalid-UTF-8 paths.
-            let filename = path.as_ref().file_name()?.to_str()?.to_ascii_lowercase();
-
-            // We need to traverse LS_COLORS from back to front
-            // to be consistent with `ls`:
-            for (suffix, style) in self.suffix_mapping.iter().rev() {
-                // Note: For some reason, 'ends_with' is much
-                // slower if we omit `.as_str()` here:
-                if filename.ends_with(suffix.as_str()) {
-                    return Some(style);
-                }
+        if let Some(indicator) = Indicator::from(entry).filter(|i| i.is_code()) {
+            self.code_mapping.insert(indicator, ansi_style.to_string());
+            return;
+        }
+
+        let style = Style::from_ansi_sequence(ansi_style);
+        // Like `ls`, an empty style (`*.foo=` or `*.foo=0`) resets the color, rather than being
+        // ignored
+        let reset = if matches!(ansi_style, "" | "0" | "00") {
+            Some(Style::default())
+        } else {
+            None
+        };
+        if let Some(suffix) = entry.strip_prefix('*') {
+            if let Some(style) = style.or(reset) {
+                self.add_suffix(suffix, style);
+            }
+        } else if let Some(indicator) = Indicator::from(entry) {
+            if indicator == Indicator::SymbolicLink {
+                self.symlink_as_target = ansi_style == "target";
+            }
+
+            if let Some(style) = style {
+                self.indicator_mapping.insert(indicator, Arc::new(style));
+            } else if let Some(style) = reset.filter(|_| indicator.is_file_type()) {
+                // Only basic file types are reset, rather than falling back to `no`. More
+                // specific types (like `ex=0`) fall back to the basic type, like in `ls`
+                self.indicator_mapping.insert(indicator, Arc::new(style));
+            } else {
+                self.indicator_mapping.remove(&indicator);
+            }
+        } else if !entry.is_empty() {
+            if let Some(style) = style.or(reset) {
+                self.extra_mapping
+                    .insert(entry.to_string(), Arc::new(style));
+            } else {
+                self.extra_mapping.remove(entry);
             }
         }
+    }
 
-        self.style_for_indicator(indicator)
+    /// Set the style for an indicator, replacing any previous style.
+    pub fn set_indicator(&mut self, indicator: Indicator, style: Style) {
+        self.set_indicator_shared(indicator, Arc::new(style));
     }
 
-    /// Get ANSI styles for each component of a given path. Components already include the path
-    /// separator symbol, if required. For a path like `foo/bar/test.md`, this would return an
-    /// iterator over three pairs for the three path components `foo/`, `bar/` and `test.md`
-    /// together with their respective styles.
-    pub fn style_for_path_components<'a>(&'a self, path: &'a Path) -> StyledComponents<'a> {
-        StyledComponents {
-            lscolors: self,
-            component_path: PathBuf::new(),
-            components: path.components().peekable(),
+    fn set_indicator_shared(&mut self, indicator: Indicator, style: Arc<Style>) {
+        if indicator == Indicator::SymbolicLink {
+            self.symlink_as_target = false;
         }
+        self.indicator_mapping.insert(indicator, style);
     }
 
-    /// Get the ANSI style for a certain `Indicator` (regular file, directory, symlink, ...). Note
-    /// that this function implements a fallback logic for some of the indicators (just like `ls`).
+    /// Add a style for file names ending in `suffix` (e.g. `".zip"`). Like suffixes parsed from
+    /// `LS_COLORS`, suffixes that are added later take precedence. An empty suffix matches every
+    /// file name.
+    pub fn add_suffix<S: Into<String>>(&mut self, suffix: S, style: Style) {
+        self.add_suffix_shared(suffix.into(), Arc::new(style));
+    }
+
+    fn add_suffix_shared(&mut self, suffix: String, style: Arc<Style>) {
+        self.suffix_trie
+            .insert(suffix.as_bytes(), self.suffix_mapping.len());
+        self.suffix_mapping.push((suffix, style));
+    }
+
+    /// Remove the style for an indicator, so that [`style_for_indicator`](#method.style_for_indicator)
+    /// falls back to the more general indicators again.
+    pub fn clear_indicator(&mut self, indicator: Indicator) {
+        if indicator == Indicator::SymbolicLink {
+            self.symlink_as_target = false;
+        }
+        self.indicator_mapping.remove(&indicator);
+        self.code_mapping.remove(&indicator);
+    }
+
+    /// Remove all file name suffix styles, while keeping the indicator styles.
+    pub fn clear_suffixes(&mut self) {
+        self.suffix_mapping.clear();
+        self.rebuild_suffix_trie();
+    }
+
+    /// Remove all styles for the given file name suffix (e.g. `".zip"`). Unless case-sensitive
+    /// matching is enabled, this also removes suffixes that only differ in case.
+    pub fn remove_suffix(&mut self, suffix: &str) {
+        let case_sensitive = self.case_sensitive;
+        self.suffix_mapping.retain(|(other, _)| {
+            if case_sensitive {
+                other != suffix
+            } else {
+                !other.eq_ignore_ascii_case(suffix)
+            }
+        });
+        self.rebuild_suffix_trie();
+    }
+
+    /// Layer another configuration on top of this one. Indicator styles from `other` replace the
+    /// ones in `self`. Suffixes from `other` are appended, so they take precedence over the
+    /// suffixes in `self` when both match a file name (just like later entries in `LS_COLORS`).
+    pub fn merge(&mut self, other: &LsColors) {
+        if other.symlink_as_target {
+            self.symlink_as_target = true;
+            self.indicator_mapping.remove(&Indicator::SymbolicLink);
+        }
+        for (indicator, style) in &other.indicator_mapping {
+            self.set_indicator_shared(*indicator, style.clone());
+        }
+        for (suffix, style) in &other.suffix_mapping {
+            self.add_suffix_shared(suffix.clone(), style.clone());
+        }
+        for (indicator, code) in &other.code_mapping {
+            self.code_mapping.insert(*indicator, code.clone());
+        }
+        for (key, style) in &other.extra_mapping {
+            self.extra_mapping.insert(key.clone(), style.clone());
+        }
+        self.custom_indicators
+            .extend(other.custom_indicators.iter().cloned());
+        for (from, to) in &other.fallback_mapping {
+            self.fallback_mapping.insert(*from, *to);
+        }
+    }
+
+    /// Change the fallback for an indicator without a style (see
+    /// [`style_for_indicator`](#method.style_for_indicator)), e.g. to style `ca` like `ex`
+    /// instead of `fi`. If `to` has no style either, `no` is used. Setting `to` to `from` itself
+    /// removes the fallback, so that only `no` is used.
+    pub fn set_fallback(&mut self, from: Indicator, to: Indicator) {
+        self.fallback_mapping.insert(from, to);
+    }
+
+    /// Get a copy of this instance that is suitable for the current terminal: if `COLORTERM` is
+    /// not set to `truecolor` or `24bit`, all 24-bit (`RGB`) colors are replaced by the closest
+    /// 8-bit (`Fixed`) colors (see [`Color::to_fixed`](style/enum.Color.html#method.to_fixed)).
+    /// Otherwise, the styles are kept as they are.
+    pub fn downgraded_for_terminal(&self) -> LsColors {
+        if supports_truecolor(env::var_os("COLORTERM").as_deref()) {
+            self.clone()
+        } else {
+            self.downgraded()
+        }
+    }
+
+    /// Get a copy of this instance with all 24-bit colors replaced by 8-bit colors.
+    fn downgraded(&self) -> LsColors {
+        let downgrade = |style: &mut Arc<Style>| {
+            if let Some(downgraded) = downgrade_style(style) {
+                *style = Arc::new(downgraded);
+            }
+        };
+
+        let mut lscolors = self.clone();
+        lscolors.indicator_mapping.values_mut().for_each(downgrade);
+        lscolors
+            .suffix_mapping
+            .iter_mut()
+            .for_each(|(_, style)| downgrade(style));
+        lscolors.extra_mapping.values_mut().for_each(downgrade);
+        lscolors
+    }
+
+    /// Iterate over all indicators that have an explicit style, in a fixed order.
+    pub fn indicators(&self) -> impl Iterator<Item = (Indicator, &Style)> {
+        INDICATORS.iter().filter_map(move |indicator| {
+            self.indicator_mapping
+                .get(indicator)
+                .map(|style| (*indicator, &**style))
+        })
+    }
+
+    /// Iterate over all rules: first the indicators that have an explicit style (like
+    /// [`indicators`](#method.indicators)), then the file name suffixes in the order they were
+    /// declared (like [`suffixes`](#method.suffixes)).
+    pub fn iter(&self) -> Rules<'_> {
+        Rules {
+            lscolors: self,
+            indicators: INDICATORS.iter(),
+            suffixes: self.suffix_mapping.iter(),
+        }
+    }
+
+    /// Compare the indicator and suffix styles of this instance to `other`. Suffixes are compared
+    /// by the style that is actually used, i.e. the last one declared for each suffix. The `lc`,
+    /// `rc` and `ec` codes and unrecognized keys are not compared.
+    pub fn diff<'a>(&'a self, other: &'a LsColors) -> LsColorsDiff<'a> {
+        let mut diff = LsColorsDiff::default();
+
+        let old: Vec<_> = self.indicators().collect();
+        let new: Vec<_> = other.indicators().collect();
+        diff.compare(&old, &new, |a, b| a == b, Rule::Indicator);
+
+        // Like the lookups, suffixes that only differ in case are the same rule (unless one of
+        // the instances is case-sensitive)
+        let case_sensitive = self.case_sensitive || other.case_sensitive;
+        diff.compare(
+            &self.effective_suffixes(),
+            &other.effective_suffixes(),
+            |a, b| suffixes_eq(a, b, case_sensitive),
+            Rule::Suffix,
+        );
+
+        diff
+    }
+
+    /// Get each declared suffix once (ignoring case, unless this instance is case-sensitive),
+    /// with the spelling of its first declaration and the style of its last declaration.
+    fn effective_suffixes(&self) -> Vec<(&str, &Style)> {
+        let mut suffixes: Vec<(&str, &Style)> = vec![];
+        for (suffix, style) in self.suffixes() {
+            match suffixes
+                .iter_mut()
+                .find(|(other, _)| suffixes_eq(other, suffix, self.case_sensitive))
+            {
+                Some(entry) => entry.1 = style,
+                None => suffixes.push((suffix, style)),
+            }
+        }
+        suffixes
+    }
+
+    /// Iterate over all file name suffixes and their styles, in the order they were declared.
+    pub fn suffixes(&self) -> impl Iterator<Item = (&str, &Style)> {
+        self.suffix_mapping
+            .iter()
+            .map(|(suffix, style)| (suffix.as_str(), &**style))
+    }
+
+    /// Get the ANSI style for a given path. If the path does not exist, the `mi` style is used if
+    /// it is set. Otherwise, the path is styled like a regular file with that name.
+    ///
+    /// *Note:* this function calls `Path::symlink_metadata` internally. If you already happen to
+    /// have the `Metadata` available, use [`style_for_path_with_metadata`](#method.style_for_path_with_metadata).
+    pub fn style_for_path<P: AsRef<Path>>(&self, path: P) -> Option<&Style> {
+        let indicator = self.indicator_for_stat(path.as_ref());
+        self.style_for_path_with_indicator(path.as_ref(), indicator)
+    }
+
+    /// Like [`style_for_path`](#method.style_for_path), but returns an owned `Style` that can
+    /// outlive this `LsColors` instance.
+    pub fn style_for_path_owned<P: AsRef<Path>>(&self, path: P) -> Option<Style> {
+        self.style_for_path(path).cloned()
+    }
+
+    /// Like [`style_for_path`](#method.style_for_path), but follows symbolic links (using
+    /// `Path::metadata` instead of `Path::symlink_metadata`), so a link to a directory is styled
+    /// like a directory. Links with a missing target are still styled as orphaned (`or`).
+    pub fn style_for_path_following_symlinks<P: AsRef<Path>>(&self, path: P) -> Option<&Style> {
+        let path = path.as_ref();
+        match path.metadata() {
+            Ok(metadata) => self.style_for_path_with_metadata(path, Some(&metadata)),
+            Err(_) => match path.symlink_metadata() {
+                Ok(metadata) => {
+                    self.style_for_path_with_metadata_and_target(path, Some(&metadata), None)
+                }
+                Err(_) => self.style_for_path(path),
+            },
+        }
+    }
+
+    /// Get the ANSI style for a file with the given name and type, without accessing the file
+    /// system. This is useful for files on remote or virtual file systems, where the file type is
+    /// known but no `Metadata` is available. Symbolic links are always styled as `ln` (or `or`),
+    /// even with `ln=target`.
+    pub fn style_for_type(&self, file_type_hint: FileTypeHint, name: &str) -> Option<&Style> {
+        let indicator = file_type_hint.indicator();
+
+        if self.matches_suffixes(indicator) {
+            if let Some(style) = self.suffix_style_for_name(name) {
+                return Some(style);
+            }
+        }
+
+        self.style_for_indicator(indicator)
+    }
+
+    /// Get the style of the file name suffix rule matching `name`, if any. This only does the
+    /// suffix matching, without accessing the file system or considering indicators, so it can be
+    /// used to cheaply check whether a file name would be colored by a suffix rule.
+    pub fn suffix_style_for_name(&self, name: &str) -> Option<&Style> {
+        self.find_suffix(name.as_bytes())
+            .map(|i| &*self.suffix_mapping[i].1)
+    }
+
+    /// Get the ANSI style for a regular file with the given name, without accessing the file
+    /// system. Unlike [`style_for_type`](#method.style_for_type), this accepts an `OsStr`, so on
+    /// Unix the suffix matching also works for names that are not valid UTF-8. On other platforms,
+    /// such names are converted lossily.
+    pub fn style_for_os_str(&self, name: &OsStr) -> Option<&Style> {
+        let rule = match crate::fs::name_bytes(name) {
+            Some(bytes) => self.find_suffix(bytes),
+            None => self.find_suffix(name.to_string_lossy().as_bytes()),
+        };
+
+        match rule {
+            Some(i) => Some(&*self.suffix_mapping[i].1),
+            None => self.style_for_indicator(Indicator::RegularFile),
+        }
+    }
+
+    /// Get the style for the final component of a path, i.e. the style a shell or file manager
+    /// would apply to the whole (clickable) path, without accessing the file system at all (see
+    /// [`style_for_path`](#method.style_for_path) for that). A path that ends with a separator,
+    /// `.` or `..` is styled as a directory, and everything else as a regular file with the given
+    /// name (see [`style_for_os_str`](#method.style_for_os_str)).
+    pub fn final_component_style_by_name<P: AsRef<Path>>(&self, path: P) -> Option<&Style> {
+        let path = path.as_ref();
+        // `components()` drops a trailing `.` (like in `foo/.`), so look at the raw path
+        let name = path.as_os_str().to_string_lossy();
+        let last = name
+            .rsplit(std::path::is_separator)
+            .next()
+            .unwrap_or_default();
+        let is_dir = matches!(last, "" | "." | "..");
+
+        match path.components().next_back() {
+            Some(Component::Normal(name)) if !is_dir => self.style_for_os_str(name),
+            Some(_) => self.style_for_indicator(Indicator::Directory),
+            None => None,
+        }
+    }
+
+    /// Get all file name suffix rules that match `name`, in the order they were declared. The last
+    /// one is the rule that is actually used (see
+    /// [`suffix_style_for_name`](#method.suffix_style_for_name)), so the others are shadowed by
+    /// it (except in [longest-match mode](#method.with_longest_match)). This is useful for linting
+    /// configurations, e.g. to warn that `*.gz` shadows `*.tar.gz`.
+    pub fn all_matching_suffixes(&self, name: &str) -> Vec<(&str, &Style)> {
+        let name = name.as_bytes();
+
+        self.suffix_mapping
+            .iter()
+            .filter(|(suffix, _)| {
+                let suffix = suffix.as_bytes();
+                name.len() >= suffix.len() && {
+                    let tail = &name[name.len() - suffix.len()..];
+                    if self.case_sensitive {
+                        tail == suffix
+                    } else {
+                        tail.eq_ignore_ascii_case(suffix)
+                    }
+                }
+            })
+            .map(|(suffix, style)| (suffix.as_str(), &**style))
+            .collect()
+    }
+
+    /// Like [`style_for_path`](#method.style_for_path), but uses `tokio::fs` to read the metadata
+    /// (if the `tokio` feature is enabled), so it does not block the async runtime. The check for
+    /// orphaned symbolic links is also done asynchronously.
+    ///
+    /// *Note:* the checks for file capabilities (`ca`) and `ln=target` are still synchronous.
+    #[cfg(feature = "tokio")]
+    pub async fn style_for_path_async<P: AsRef<Path>>(&self, path: P) -> Option<&Style> {
+        let path = path.as_ref();
+        let metadata = match tokio::fs::symlink_metadata(path).await {
+            Ok(metadata) => Some(metadata),
+            Err(err)
+                if err.kind() == io::ErrorKind::NotFound
+                    && self.has_color_for(Indicator::MissingFile) =>
+            {
+                return self.style_for_path_with_indicator(path, Indicator::MissingFile);
+            }
+            Err(_) => None,
+        };
+
+        let target_exists = match &metadata {
+            Some(metadata) if metadata.file_type().is_symlink() => {
+                Some(tokio::fs::metadata(path).await.is_ok())
+            }
+            _ => None,
+        };
+
+        let indicator = self.indicator_for(path, metadata.as_ref(), target_exists);
+        self.style_for_path_with_indicator(path, indicator)
+    }
+
+    /// Classify a path with the given metadata (acquired via `Path::symlink_metadata`). Like `ls`,
+    /// only indicators that have a style are considered, e.g. a setuid file is only classified as
+    /// `Setuid` if there is a style for `su`. Without metadata, the path is classified as a
+    /// regular file.
+    pub fn indicator_for_path<P: AsRef<Path>>(
+        &self,
+        path: P,
+        metadata: Option<&std::fs::Metadata>,
+    ) -> Indicator {
+        self.indicator_for(path.as_ref(), metadata, None)
+    }
+
+    /// Classify a path, using `Path::symlink_metadata` to get its metadata. A path that does not
+    /// exist is classified as `MissingFile` if there is a style for `mi`, otherwise (like without
+    /// metadata) as a regular file, so that file name suffixes still apply.
+    fn indicator_for_stat(&self, path: &Path) -> Indicator {
+        match path.symlink_metadata() {
+            Ok(metadata) => self.indicator_for(path, Some(&metadata), None),
+            Err(err)
+                if err.kind() == io::ErrorKind::NotFound
+                    && self.has_color_for(Indicator::MissingFile) =>
+            {
+                Indicator::MissingFile
+            }
+            Err(_) => self.indicator_for(path, None, None),
+        }
+    }
+
+    /// Check if an indicator has an associated color.
+    fn has_color_for(&self, indicator: Indicator) -> bool {
+        self.indicator_mapping.contains_key(&indicator)
+    }
+
+    /// Get the indicator type for a path with corresponding metadata.
+    /// If `target_exists` is `None`, the target of a symbolic link is checked on demand.
+    fn indicator_for(
+        &self,
+        path: &Path,
+        metadata: Option<&std::fs::Metadata>,
+        target_exists: Option<bool>,
+    ) -> Indicator {
+        if let Some(metadata) = metadata {
+            self.indicator_for_file_type(path, metadata.file_type(), Some(metadata), target_exists)
+        } else {
+            // Default to a regular file, so we still try the suffix map when no metadata is available
+            Indicator::RegularFile
+        }
+    }
+
+    /// Check whether the full metadata is needed (in addition to the file type) to determine the
+    /// indicator for a file, i.e. whether the mode bits or the number of links are relevant.
+    fn needs_metadata(&self, file_type: std::fs::FileType) -> bool {
+        let needs_attributes = cfg!(windows) && self.has_color_for(Indicator::Hidden);
+
+        if file_type.is_file() {
+            self.has_color_for(Indicator::Setuid)
+                || self.has_color_for(Indicator::Setgid)
+                || self.has_color_for(Indicator::ExecutableFile)
+                || self.has_color_for(Indicator::MultipleHardLinks)
+                || needs_attributes
+                || (cfg!(windows) && self.has_color_for(Indicator::ReadOnly))
+        } else if file_type.is_dir() {
+            self.has_color_for(Indicator::StickyAndOtherWritable)
+                || self.has_color_for(Indicator::OtherWritable)
+                || self.has_color_for(Indicator::Sticky)
+                || needs_attributes
+        } else {
+            !file_type.is_symlink()
+        }
+    }
+
+    /// Get the indicator type for a path with the given file type. The metadata is only needed if
+    /// [`needs_metadata`](#method.needs_metadata) returns `true`. If `target_exists` is `None`,
+    /// the target of a symbolic link is checked on demand.
+    fn indicator_for_file_type(
+        &self,
+        path: &Path,
+        file_type: std::fs::FileType,
+        metadata: Option<&std::fs::Metadata>,
+        target_exists: Option<bool>,
+    ) -> Indicator {
+        let attributes = metadata.map_or(0, crate::fs::file_attributes);
+        if (file_type.is_file() || file_type.is_dir())
+            && self.has_color_for(Indicator::Hidden)
+            && attributes & crate::fs::FILE_ATTRIBUTE_HIDDEN != 0
+        {
+            return Indicator::Hidden;
+        }
+
+        if file_type.is_file() {
+            let mode = metadata.map_or(0, crate::fs::mode);
+            let nlink = metadata.map_or(1, crate::fs::nlink);
+
+            self.indicator_for_regular_file(mode, nlink, attributes, || {
+                crate::fs::has_capabilities(path)
+            })
+        } else if file_type.is_dir() {
+            self.indicator_for_directory(metadata.map_or(0, crate::fs::mode))
+        } else if file_type.is_symlink() {
+            // This works because `Path::exists` traverses symlinks. Like `ls`, broken links are
+            // always orphans with `ln=target`, even if `or` has no style.
+            if (self.symlink_as_target || self.has_color_for(Indicator::OrphanedSymbolicLink))
+                && !target_exists.unwrap_or_else(|| path.exists())
+            {
+                return Indicator::OrphanedSymbolicLink;
+            }
+
+            Indicator::SymbolicLink
+        } else {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::FileTypeExt;
+
+                if file_type.is_fifo() {
+                    return Indicator::FIFO;
+                }
+                if file_type.is_socket() {
+                    return Indicator::Socket;
+                }
+                if file_type.is_block_device() {
+                    return Indicator::BlockDevice;
+                }
+                if file_type.is_char_device() {
+                    return Indicator::CharacterDevice;
+                }
+                if matches!(metadata, Some(md) if crate::fs::is_door(md)) {
+                    return Indicator::Door;
+                }
+            }
+
+            // Treat files of unknown type as errors
+            Indicator::MissingFile
+        }
+    }
+
+    /// Classify a regular file. Like in GNU `ls`, the precedence is `su` > `sg` > `ca` > `ex` >
+    /// `mh` > `fi`, where only indicators that have a style are considered. The (expensive) check
+    /// for file capabilities is only done if necessary.
+    fn indicator_for_regular_file<F: FnOnce() -> bool>(
+        &self,
+        mode: u32,
+        nlink: u64,
+        attributes: u32,
+        has_capabilities: F,
+    ) -> Indicator {
+        if self.has_color_for(Indicator::Setuid) && mode & 0o4000 != 0 {
+            Indicator::Setuid
+        } else if self.has_color_for(Indicator::Setgid) && mode & 0o2000 != 0 {
+            Indicator::Setgid
+        } else if self.has_color_for(Indicator::Capabilities) && has_capabilities() {
+            Indicator::Capabilities
+        } else if self.has_color_for(Indicator::ExecutableFile) && mode & 0o0111 != 0 {
+            Indicator::ExecutableFile
+        } else if self.has_color_for(Indicator::MultipleHardLinks) && nlink > 1 {
+            Indicator::MultipleHardLinks
+        } else if self.has_color_for(Indicator::ReadOnly)
+            && attributes & crate::fs::FILE_ATTRIBUTE_READONLY != 0
+        {
+            Indicator::ReadOnly
+        } else {
+            Indicator::RegularFile
+        }
+    }
+
+    /// Classify a directory. Like in GNU `ls`, the precedence is `tw` > `ow` > `st` > `di`, where
+    /// only indicators that have a style are considered. So a sticky, other-writable directory is
+    /// `ow` if only `ow` has a style.
+    fn indicator_for_directory(&self, mode: u32) -> Indicator {
+        if self.has_color_for(Indicator::StickyAndOtherWritable) && mode & 0o1002 == 0o1002 {
+            Indicator::StickyAndOtherWritable
+        } else if self.has_color_for(Indicator::OtherWritable) && mode & 0o0002 != 0 {
+            Indicator::OtherWritable
+        } else if self.has_color_for(Indicator::Sticky) && mode & 0o1000 != 0 {
+            Indicator::Sticky
+        } else {
+            Indicator::Directory
+        }
+    }
+
+    /// Get the ANSI style for a path, given the corresponding `Metadata` struct.
+    ///
+    /// *Note:* The `Metadata` struct must have been acquired via `Path::symlink_metadata` in
+    /// order to colorize symbolic links correctly.
+    pub fn style_for_path_with_metadata<P: AsRef<Path>>(
+        &self,
+        path: P,
+        metadata: Option<&std::fs::Metadata>,
+    ) -> Option<&Style> {
+        let indicator = self.indicator_for(path.as_ref(), metadata, None);
+        self.style_for_path_with_indicator(path.as_ref(), indicator)
+    }
+
+    /// Like [`style_for_path_with_metadata`](#method.style_for_path_with_metadata), but also takes
+    /// the `Metadata` of the target of a symbolic link (as returned by `Path::metadata`). This
+    /// avoids a second `stat` call to check whether the link is orphaned, if the caller already
+    /// has the target metadata available. If `target_metadata` is `None`, a symbolic link is
+    /// considered to be orphaned.
+    pub fn style_for_path_with_metadata_and_target<P: AsRef<Path>>(
+        &self,
+        path: P,
+        metadata: Option<&std::fs::Metadata>,
+        target_metadata: Option<&std::fs::Metadata>,
+    ) -> Option<&Style> {
+        let indicator =
+            self.indicator_for(path.as_ref(), metadata, Some(target_metadata.is_some()));
+        self.style_for_path_with_indicator(path.as_ref(), indicator)
+    }
+
+    /// Get the ANSI styles for many paths at once, together with their (optional) `Metadata`. This
+    /// is the same as calling [`style_for_path_with_metadata`](#method.style_for_path_with_metadata)
+    /// for each item, and the styles are returned in the same order.
+    pub fn styles_for_paths<'a, I>(
+        &'a self,
+        items: I,
+    ) -> impl Iterator<Item = Option<&'a Style>> + 'a
+    where
+        I: IntoIterator<Item = (&'a Path, Option<&'a std::fs::Metadata>)>,
+        I::IntoIter: 'a,
+    {
+        items
+            .into_iter()
+            .map(move |(path, metadata)| self.style_for_path_with_metadata(path, metadata))
+    }
+
+    /// Get the ANSI styles for many paths in parallel (if the `rayon` feature is enabled). Each
+    /// path is classified like in [`style_for_path`](#method.style_for_path), including the file
+    /// system access, so this also helps on slow or networked file systems. The styles are
+    /// returned in the same order as the paths.
+    #[cfg(feature = "rayon")]
+    pub fn par_styles_for_paths(&self, paths: &[PathBuf]) -> Vec<Option<Style>> {
+        use rayon::prelude::*;
+
+        paths
+            .par_iter()
+            .map(|path| self.style_for_path_owned(path))
+            .collect()
+    }
+
+    /// Like [`style_for_path`](#method.style_for_path), but caches the file name suffix lookups by
+    /// extension. This helps tools that color many files with the same extensions. The file
+    /// system is still accessed for every path.
+    pub fn style_for_path_cached<P: AsRef<Path>>(
+        &self,
+        cache: &mut StyleCache,
+        path: P,
+    ) -> Option<&Style> {
+        let path = path.as_ref();
+        let indicator = self.indicator_for_stat(path);
+
+        self.style_and_reason_for_path_with_suffix(path, indicator, |filename| {
+            self.find_suffix_cached(cache, filename)
+        })
+        .map(|(style, _)| style)
+    }
+
+    /// Find the index of the suffix rule matching a file name, using the cache if possible.
+    fn find_suffix_cached(&self, cache: &mut StyleCache, filename: &[u8]) -> Option<usize> {
+        let extension = match filename.iter().rposition(|&b| b == b'.') {
+            Some(dot) => &filename[dot..],
+            None => return self.find_suffix(filename),
+        };
+
+        let key = if self.case_sensitive || !extension.iter().any(u8::is_ascii_uppercase) {
+            Cow::Borrowed(extension)
+        } else {
+            Cow::Owned(extension.to_ascii_lowercase())
+        };
+
+        let cached = match cache.extensions.get(key.as_ref()) {
+            Some(cached) => *cached,
+            None => {
+                let ambiguous = self.suffix_mapping.iter().any(|(suffix, _)| {
+                    let suffix = suffix.as_bytes();
+                    suffix.len() > key.len() && {
+                        let tail = &suffix[suffix.len() - key.len()..];
+                        if self.case_sensitive {
+                            tail == key.as_ref()
+                        } else {
+                            tail.eq_ignore_ascii_case(&key)
+                        }
+                    }
+                });
+
+                let cached = if ambiguous {
+                    CachedSuffix::Ambiguous
+                } else {
+                    CachedSuffix::Rule(self.find_suffix(filename))
+                };
+                cache.extensions.insert(key.into_owned(), cached);
+                cached
+            }
+        };
+
+        match cached {
+            CachedSuffix::Rule(rule) => rule,
+            CachedSuffix::Ambiguous => self.find_suffix(filename),
+        }
+    }
+
+    /// Get the ANSI style for a directory entry. This uses the file type from the `DirEntry`,
+    /// which is usually available without an additional system call, and only reads the full
+    /// metadata if it is needed for the configured indicators (e.g. `su`, `ex` or `tw`).
+    pub fn style_for_dir_entry(&self, entry: &std::fs::DirEntry) -> Option<&Style> {
+        let path = entry.path();
+
+        match entry.file_type() {
+            Ok(file_type) if !self.needs_metadata(file_type) => {
+                let indicator = self.indicator_for_file_type(&path, file_type, None, None);
+                self.style_for_path_with_indicator(&path, indicator)
+            }
+            _ => {
+                let metadata = entry.metadata().ok();
+                self.style_for_path_with_metadata(&path, metadata.as_ref())
+            }
+        }
+    }
+
+    /// Get the ANSI style for a `walkdir::DirEntry` (if the `walkdir` feature is enabled). Like
+    /// [`style_for_dir_entry`](#method.style_for_dir_entry), this reuses the file type from the
+    /// entry. If `walkdir` was configured to follow symbolic links, followed links are styled as
+    /// `ln` without checking whether they are orphaned (`walkdir` already resolved them).
+    #[cfg(feature = "walkdir")]
+    pub fn style_for_walkdir_entry(&self, entry: &walkdir::DirEntry) -> Option<&Style> {
+        let path = entry.path();
+        let file_type = entry.file_type();
+
+        let indicator = if entry.path_is_symlink() && !file_type.is_symlink() {
+            Indicator::SymbolicLink
+        } else if self.needs_metadata(file_type) {
+            let metadata = entry.metadata().ok();
+            self.indicator_for_file_type(path, file_type, metadata.as_ref(), None)
+        } else {
+            self.indicator_for_file_type(path, file_type, None, None)
+        };
+
+        self.style_for_path_with_indicator(path, indicator)
+    }
+
+    /// Like [`style_for_path`](#method.style_for_path), but also returns the reason why the style
+    /// was chosen, i.e. the indicator or the file name suffix rule that matched. This can be used
+    /// to explain the colors to users.
+    pub fn style_for_path_with_reason<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Option<(&Style, MatchReason<'_>)> {
+        let path = path.as_ref();
+        let indicator = self.indicator_for_stat(path);
+        self.style_and_reason_for_path_with_indicator(path, indicator)
+    }
+
+    /// Get the ANSI style for the target of a symbolic link (for `ln=target`). Like `ls`, the file
+    /// name of the link target is used for suffix matching.
+    fn style_for_symlink_target(
+        &self,
+        path: &Path,
+        target_metadata: &std::fs::Metadata,
+    ) -> Option<(&Style, MatchReason<'_>)> {
+        let target = match (std::fs::read_link(path), path.parent()) {
+            (Ok(target), Some(parent)) => parent.join(target),
+            (Ok(target), None) => target,
+            (Err(_), _) => path.to_path_buf(),
+        };
+
+        // `Path::metadata` follows all symlinks, so this can not recurse again
+        let indicator = self.indicator_for(&target, Some(target_metadata), Some(true));
+        self.style_and_reason_for_path_with_indicator(&target, indicator)
+    }
+
+    /// Get the ANSI style for a path with a known indicator type.
+    fn style_for_path_with_indicator(&self, path: &Path, indicator: Indicator) -> Option<&Style> {
+        self.style_and_reason_for_path_with_indicator(path, indicator)
+            .map(|(style, _)| style)
+    }
+
+    /// Get the ANSI style for a path with a known indicator type, and the reason why it matched.
+    fn style_and_reason_for_path_with_indicator(
+        &self,
+        path: &Path,
+        indicator: Indicator,
+    ) -> Option<(&Style, MatchReason<'_>)> {
+        self.style_and_reason_for_path_with_suffix(path, indicator, |filename| {
+            self.find_suffix(filename)
+        })
+    }
+
+    /// Like `style_and_reason_for_path_with_indicator`, but with a custom function to find the
+    /// index of the suffix rule that matches a file name (e.g. via a [`StyleCache`]).
+    fn style_and_reason_for_path_with_suffix<F>(
+        &self,
+        path: &Path,
+        indicator: Indicator,
+        find_suffix: F,
+    ) -> Option<(&Style, MatchReason<'_>)>
+    where
+        F: FnOnce(&[u8]) -> Option<usize>,
+    {
+        if indicator == Indicator::SymbolicLink && self.symlink_as_target {
+            if let Ok(target_metadata) = path.metadata() {
+                return self.style_for_symlink_target(path, &target_metadata);
+            }
+        }
+
+        if self.matches_suffixes(indicator) {
+            // Note: on non-Unix platforms, filename
+            // matching will not work with invalid-UTF-8 paths.
+            if let Some(filename) = path.file_name().and_then(crate::fs::name_bytes) {
+                // The last matching entry wins, to be consistent with `ls`
+                if let Some(i) = find_suffix(filename) {
+                    let (suffix, style) = &self.suffix_mapping[i];
+                    return Some((style, MatchReason::Suffix(suffix)));
+                }
+            }
+        }
+
+        self.indicator_style(indicator)
+            .map(|(indicator, style)| (style, MatchReason::Indicator(indicator)))
+    }
+
+    /// Paint some text with the given style. The returned value implements `Display` and can be
+    /// used to print the text without depending on `ansi_term` or `crossterm`. If no style is
+    /// given, the text is printed as-is, without any escape sequences.
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")]
+    /// # {
+    /// use lscolors::LsColors;
+    ///
+    /// let lscolors = LsColors::from_string("*.zip=01;31");
+    /// let style = lscolors.style_for_path("file.zip");
+    /// let painted = lscolors.paint("file.zip", style).to_string();
+    /// assert_eq!("\x1b[1;31mfile.zip\x1b[0m", painted);
+    /// # }
+    /// ```
+    pub fn paint<'a>(&'a self, text: &'a str, style: Option<&'a Style>) -> Painted<'a> {
+        Painted {
+            text,
+            style,
+            left_code: self.left_code(),
+            right_code: self.right_code(),
+            end_code: self.end_code(),
+        }
+    }
+
+    /// Get the `lc` code that starts an escape sequence (`"\x1b["` by default). Note that an 8-bit
+    /// code like `lc=\233` becomes the character U+009B, which is two bytes in UTF-8, while `ls`
+    /// writes the single byte `0x9b`.
+    pub fn left_code(&self) -> &str {
+        self.code_mapping
+            .get(&Indicator::LeftCode)
+            .map_or("\x1b[", String::as_str)
+    }
+
+    /// Get the `rc` code that ends an escape sequence (`"m"` by default).
+    pub fn right_code(&self) -> &str {
+        self.code_mapping
+            .get(&Indicator::RightCode)
+            .map_or("m", String::as_str)
+    }
+
+    /// Get the `ec` code that ends a colored file name, if it is set. Otherwise (like `ls`), the
+    /// style is reset with the `lc` code, `0` and the `rc` code.
+    pub fn end_code(&self) -> Option<&str> {
+        self.code_mapping
+            .get(&Indicator::EndCode)
+            .map(String::as_str)
+    }
+
+    /// Get ANSI styles for each component of a given path. Components already include the path
+    /// separator symbol, if required. For a path like `foo/bar/test.md`, this would return an
+    /// iterator over three pairs for the three path components `foo/`, `bar/` and `test.md`
+    /// together with their respective styles. If the path ends with a separator (`foo/bar/`), the
+    /// last component includes it as well (`bar/`).
+    pub fn style_for_path_components<'a>(&'a self, path: &'a Path) -> StyledComponents<'a> {
+        self.style_for_path_components_with_separator(path, MAIN_SEPARATOR)
+    }
+
+    /// Like [`style_for_path_components`](#method.style_for_path_components), but with a custom
+    /// separator instead of the platform's `MAIN_SEPARATOR`, e.g. to render paths with `/` on
+    /// Windows.
+    pub fn style_for_path_components_with_separator<'a>(
+        &'a self,
+        path: &'a Path,
+        separator: char,
+    ) -> StyledComponents<'a> {
+        StyledComponents {
+            lscolors: self,
+            component_path: PathBuf::new(),
+            components: path.components().peekable(),
+            trailing_separator: path
+                .as_os_str()
+                .to_string_lossy()
+                .ends_with(std::path::is_separator),
+            separator,
+        }
+    }
+
+    /// Like [`style_for_path_components`](#method.style_for_path_components), but yields each
+    /// component already painted (see [`paint`](#method.paint)), so the items can simply be
+    /// concatenated. Components that are not valid UTF-8 are converted lossily.
+    pub fn painted_components<'a>(&'a self, path: &'a Path) -> impl Iterator<Item = String> + 'a {
+        self.style_for_path_components(path)
+            .map(move |(component, style)| {
+                self.paint(&component.to_string_lossy(), style).to_string()
+            })
+    }
+
+    /// Render a sample of all configured styles, similar to `dircolors --print-database` but
+    /// colorized: one painted label (see [`paint`](#method.paint)) for each indicator with an
+    /// explicit style (like `di -> directory`), followed by one for each file name suffix in the
+    /// order they were declared (like `*.zip -> archive`).
+    pub fn sample_lines(&self) -> Vec<String> {
+        let indicators = self.indicators().map(|(indicator, style)| {
+            let label = format!("{} -> {}", indicator.to_str(), indicator.description());
+            self.paint(&label, Some(style)).to_string()
+        });
+        let suffixes = self.suffixes().map(|(suffix, style)| {
+            let label = format!("*{} -> {}", suffix, suffix_category(suffix));
+            self.paint(&label, Some(style)).to_string()
+        });
+
+        indicators.chain(suffixes).collect()
+    }
+
+    /// Write a path to `writer`, with each component styled independently (like
+    /// [`style_for_path_components`](#method.style_for_path_components)). Each styled component,
+    /// including its separator, is wrapped in its escape sequence and a reset.
+    pub fn write_path_components<W: io::Write>(
+        &self,
+        path: &Path,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        for (component, style) in self.style_for_path_components(path) {
+            if let Some(style) = style {
+                write!(
+                    writer,
+                    "{}{}{}",
+                    self.left_code(),
+                    style.to_ansi_sequence(),
+                    self.right_code()
+                )?;
+            }
+
+            match crate::fs::name_bytes(&component) {
+                Some(bytes) => writer.write_all(bytes)?,
+                None => writer.write_all(component.to_string_lossy().as_bytes())?,
+            }
+
+            if style.is_some() {
+                match self.end_code() {
+                    Some(end_code) => writer.write_all(end_code.as_bytes())?,
+                    None => write!(writer, "{}0{}", self.left_code(), self.right_code())?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the ANSI style for a certain `Indicator` (regular file, directory, symlink, ...). Note
+    /// that this function implements a fallback logic for some of the indicators (just like `ls`).
     /// For example, the style for `mi` (missing file) falls back to `or` (orphaned symbolic link)
-    /// if it has not been specified explicitly.
+    /// if it has not been specified explicitly. The fallbacks can be changed with
+    /// [`set_fallback`](#method.set_fallback).
     pub fn style_for_indicator(&self, indicator: Indicator) -> Option<&Style> {
-        self.indicator_mapping
-            .get(&indicator)
+        self.indicator_style(indicator).map(|(_, style)| style)
+    }
+
+    /// Get the style for a key in `LS_COLORS` that is not a known indicator, like the extra codes
+    /// used by tools such as `eza` (e.g. `uu` for the current user). Such keys are kept as-is
+    /// when parsing, so applications can build richer color schemes on top of `LS_COLORS`, e.g.
+    /// with their own keys for file sizes (`sz=32`) or dates (`da=34`). Every `key=value` entry
+    /// with a valid style is available, but keys that are registered with
+    /// [`register_indicator`](#method.register_indicator) also fall back to the `no` style.
+    pub fn style_for_custom(&self, key: &str) -> Option<&Style> {
+        self.extra_mapping
+            .get(key)
+            .map(|style| &**style)
             .or_else(|| {
-                self.indicator_mapping.get(&match indicator {
-                    Indicator::Setuid
-                    | Indicator::Setgid
-                    | Indicator::ExecutableFile
-                    | Indicator::MultipleHardLinks => Indicator::RegularFile,
+                if self.custom_indicators.contains(key) {
+                    self.style_for_indicator(Indicator::Normal)
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Register a custom indicator key, like `sz` for file sizes in an `ls` replacement. Entries
+    /// for unknown keys are always kept when parsing, but a registered key behaves like a
+    /// standard indicator in [`style_for_custom`](#method.style_for_custom): without a style of
+    /// its own, it falls back to the `no` style for normal (non-filename) text. The keys of
+    /// standard indicators (like `di`) can not be registered, and are ignored.
+    pub fn register_indicator(&mut self, key: &str) {
+        if Indicator::from(key).is_none() {
+            self.custom_indicators.insert(key.to_string());
+        }
+    }
+
+    /// Like [`style_for_indicator`](#method.style_for_indicator), but returns an owned `Style` that
+    /// can outlive this `LsColors` instance.
+    pub fn style_for_indicator_owned(&self, indicator: Indicator) -> Option<Style> {
+        self.style_for_indicator(indicator).cloned()
+    }
+
+    /// Like [`style_for_indicator`](#method.style_for_indicator), but also returns the indicator
+    /// whose style was used after applying the fallback logic.
+    fn indicator_style(&self, indicator: Indicator) -> Option<(Indicator, &Style)> {
+        let get = |indicator| {
+            self.indicator_mapping
+                .get(&indicator)
+                .map(|style| (indicator, &**style))
+        };
+
+        let fallback = self
+            .fallback_mapping
+            .get(&indicator)
+            .copied()
+            .or_else(|| indicator.default_fallback());
+
+        get(indicator)
+            .or_else(|| fallback.and_then(get))
+            .or_else(|| get(Indicator::Normal))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::style::{Color, FontStyle, Style};
+    use crate::{
+        colors_enabled, supports_truecolor, FileTypeHint, Indicator, LsColors, LsColorsDiff,
+        MatchReason, Rule, StyleCache, INDICATORS,
+    };
+
+    use std::ffi::OsStr;
+    use std::fs::{self, File};
+    use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+
+    #[test]
+    fn basic_usage() {
+        let lscolors = LsColors::default();
+
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(FontStyle::bold(), style_dir.font_style);
+        assert_eq!(Some(Color::Blue), style_dir.foreground);
+        assert_eq!(None, style_dir.background);
+
+        let style_symlink = lscolors
+            .style_for_indicator(Indicator::SymbolicLink)
+            .unwrap();
+        assert_eq!(FontStyle::bold(), style_symlink.font_style);
+        assert_eq!(Some(Color::Cyan), style_symlink.foreground);
+        assert_eq!(None, style_symlink.background);
+
+        let style_rs = lscolors.style_for_path("test.wav").unwrap();
+        assert_eq!(FontStyle::default(), style_rs.font_style);
+        assert_eq!(Some(Color::Cyan), style_rs.foreground);
+        assert_eq!(None, style_rs.background);
+    }
+
+    #[test]
+    fn style_for_path_uses_correct_ordering() {
+        let lscolors = LsColors::from_string("*.foo=01;35:*README.foo=33;44");
+
+        let style_foo = lscolors.style_for_path("some/folder/dummy.foo").unwrap();
+        assert_eq!(FontStyle::bold(), style_foo.font_style);
+        assert_eq!(Some(Color::Magenta), style_foo.foreground);
+        assert_eq!(None, style_foo.background);
+
+        let style_readme = lscolors
+            .style_for_path("some/other/folder/README.foo")
+            .unwrap();
+        assert_eq!(FontStyle::default(), style_readme.font_style);
+        assert_eq!(Some(Color::Yellow), style_readme.foreground);
+        assert_eq!(Some(Color::Blue), style_readme.background);
+    }
+
+    #[test]
+    fn style_for_path_uses_lowercase_matching() {
+        let lscolors = LsColors::from_string("*.O=01;35");
+
+        let style_artifact = lscolors.style_for_path("artifact.o").unwrap();
+        assert_eq!(FontStyle::bold(), style_artifact.font_style);
+        assert_eq!(Some(Color::Magenta), style_artifact.foreground);
+        assert_eq!(None, style_artifact.background);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn style_for_path_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let lscolors = LsColors::default();
+        let path = Path::new(OsStr::from_bytes(b"music/\xffsong.MP3"));
+
+        let style = lscolors.style_for_path(path).unwrap();
+        assert_eq!(Some(Color::Cyan), style.foreground);
+    }
+
+    #[test]
+    fn style_for_path_case_sensitive() {
+        let lscolors = LsColors::from_string("*.C=01;35").with_case_sensitive(true);
+
+        let style_upper = lscolors.style_for_path("foo.C").unwrap();
+        assert_eq!(Some(Color::Magenta), style_upper.foreground);
+        assert_eq!(None, lscolors.style_for_path("foo.c"));
+    }
+
+    #[test]
+    fn default_styles_should_be_preserved() {
+        // Setting an unrelated style should not influence the default
+        // style for "directory" (below)
+        let lscolors = LsColors::from_string("ex=01:");
+
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(FontStyle::bold(), style_dir.font_style);
+        assert_eq!(Some(Color::Blue), style_dir.foreground);
+        assert_eq!(None, style_dir.background);
+    }
+
+    #[test]
+    fn set_indicator_and_add_suffix() {
+        let mut lscolors = LsColors::empty();
+        lscolors.set_indicator(
+            Indicator::Directory,
+            Style {
+                foreground: Some(Color::Green),
+                ..Style::default()
+            },
+        );
+        lscolors.add_suffix(
+            ".TXT",
+            Style {
+                font_style: FontStyle::bold(),
+                ..Style::default()
+            },
+        );
+
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Green), style_dir.foreground);
+
+        let style_txt = lscolors.style_for_path("notes.txt").unwrap();
+        assert_eq!(FontStyle::bold(), style_txt.font_style);
+    }
+
+    #[test]
+    fn clear_indicator_and_suffixes() {
+        let mut lscolors = LsColors::default();
+        lscolors.clear_indicator(Indicator::Directory);
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::Directory));
+        assert!(lscolors
+            .style_for_indicator(Indicator::SymbolicLink)
+            .is_some());
+
+        lscolors.set_indicator(Indicator::Normal, Style::default());
+        assert_eq!(
+            Some(&Style::default()),
+            lscolors.style_for_indicator(Indicator::Directory)
+        );
+
+        lscolors.remove_suffix(".ZIP");
+        assert_eq!(
+            Some(&Style::default()),
+            lscolors.style_for_path("archive.zip")
+        );
+        let style_tar = lscolors.style_for_path("archive.tar").unwrap();
+        assert_eq!(Some(Color::Red), style_tar.foreground);
+
+        lscolors.clear_suffixes();
+        assert_eq!(0, lscolors.suffixes().count());
+        assert_eq!(
+            Some(&Style::default()),
+            lscolors.style_for_path("archive.tar")
+        );
+        assert!(lscolors
+            .style_for_indicator(Indicator::SymbolicLink)
+            .is_some());
+    }
+
+    #[test]
+    fn style_for_path_with_reason() {
+        let tmp_dir = temp_dir();
+        let lscolors = LsColors::from_string("no=33:*.png=01;35");
+
+        let png = create_file(tmp_dir.path().join("image.png"));
+        let (style, reason) = lscolors.style_for_path_with_reason(&png).unwrap();
+        assert_eq!(Some(Color::Magenta), style.foreground);
+        assert_eq!(MatchReason::Suffix(".png"), reason);
+
+        let dir = create_dir(tmp_dir.path().join("dir"));
+        let (style, reason) = lscolors.style_for_path_with_reason(&dir).unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+        assert_eq!(MatchReason::Indicator(Indicator::Directory), reason);
+
+        let txt = create_file(tmp_dir.path().join("notes.txt"));
+        let (style, reason) = lscolors.style_for_path_with_reason(&txt).unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+        assert_eq!(MatchReason::Indicator(Indicator::Normal), reason);
+    }
+
+    #[test]
+    fn style_for_type() {
+        let lscolors = LsColors::from_string("ex=01;32:ln=target:*.zip=31");
+
+        let style = lscolors
+            .style_for_type(FileTypeHint::Directory, "archive.zip")
+            .unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+
+        let style = lscolors
+            .style_for_type(FileTypeHint::File, "archive.zip")
+            .unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+
+        let style = lscolors
+            .style_for_type(FileTypeHint::ExecutableFile, "run.zip")
+            .unwrap();
+        assert_eq!(Some(Color::Green), style.foreground);
+
+        let style = lscolors.style_for_type(FileTypeHint::FIFO, "pipe").unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+
+        assert_eq!(
+            None,
+            lscolors.style_for_type(FileTypeHint::File, "notes.txt")
+        );
+    }
+
+    #[test]
+    fn owned_styles() {
+        let style = {
+            let lscolors = LsColors::default();
+            assert_eq!(
+                lscolors.style_for_indicator(Indicator::Directory).cloned(),
+                lscolors.style_for_indicator_owned(Indicator::Directory)
+            );
+            assert_eq!(
+                lscolors.style_for_path("archive.zip").cloned(),
+                lscolors.style_for_path_owned("archive.zip")
+            );
+            lscolors.style_for_indicator_owned(Indicator::Directory)
+        };
+        assert_eq!(Some(Color::Blue), style.unwrap().foreground);
+    }
+
+    #[test]
+    fn register_indicator() {
+        let mut lscolors = LsColors::empty();
+        lscolors.register_indicator("sz");
+        lscolors.register_indicator("da");
+        lscolors.register_indicator("di");
+        lscolors.add_from_string("no=37:sz=32:di=34");
+
+        let style = lscolors.style_for_custom("sz").unwrap();
+        assert_eq!(Some(Color::Green), style.foreground);
+
+        // Registered keys without a style fall back to `no`, unlike unregistered ones
+        let style = lscolors.style_for_custom("da").unwrap();
+        assert_eq!(Some(Color::White), style.foreground);
+        assert_eq!(None, lscolors.style_for_custom("uu"));
+        assert_eq!(None, lscolors.style_for_custom("di"));
+
+        let mut merged = LsColors::empty();
+        merged.merge(&lscolors);
+        assert_eq!(
+            lscolors.style_for_custom("da"),
+            merged.style_for_custom("da")
+        );
+    }
+
+    #[test]
+    fn custom_keys() {
+        let lscolors = LsColors::from_string("zz=31:uu=01;33:di=34");
+
+        let style = lscolors.style_for_custom("zz").unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        let style = lscolors.style_for_custom("uu").unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+        assert_eq!(FontStyle::bold(), style.font_style);
+        assert_eq!(None, lscolors.style_for_custom("di"));
+        assert_eq!(None, lscolors.style_for_custom("gu"));
+
+        // Application-specific keys work the same way
+        let lscolors_sizes = LsColors::from_string("sz=32:da=04;34");
+        let style = lscolors_sizes.style_for_custom("sz").unwrap();
+        assert_eq!(Some(Color::Green), style.foreground);
+        let style = lscolors_sizes.style_for_custom("da").unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+        assert!(style.font_style.is_underlined());
+
+        let string = lscolors.to_string();
+        assert!(string.ends_with(":uu=1;33:zz=31:"));
+        let reparsed = LsColors::from_string(&string);
+        assert_eq!(
+            lscolors.style_for_custom("zz"),
+            reparsed.style_for_custom("zz")
+        );
+
+        let mut merged = LsColors::empty();
+        merged.merge(&lscolors);
+        assert!(merged.style_for_custom("zz").is_some());
+    }
+
+    #[test]
+    fn iter_rules() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("*.zip=31:di=34:*.png=35:ln=36");
+
+        let rules: Vec<_> = lscolors.iter().collect();
+        assert_eq!(4, rules.len());
+        assert!(matches!(rules[0], Rule::Indicator(Indicator::Directory, _)));
+        assert!(matches!(
+            rules[1],
+            Rule::Indicator(Indicator::SymbolicLink, _)
+        ));
+        assert!(matches!(rules[2], Rule::Suffix(".zip", _)));
+        assert!(matches!(rules[3], Rule::Suffix(".png", _)));
+
+        let mut count = 0;
+        for rule in &lscolors {
+            if let Rule::Suffix(suffix, style) = rule {
+                assert_eq!(lscolors.suffix_style_for_name(suffix), Some(style));
+            }
+            count += 1;
+        }
+        assert_eq!(4, count);
+
+        assert_eq!(
+            LsColors::default().indicators().count() + LsColors::default().suffixes().count(),
+            LsColors::default().iter().count()
+        );
+    }
+
+    #[test]
+    fn values_containing_equals_signs() {
+        let lscolors = LsColors::from_string("ec=a=b:di=34");
+        assert_eq!(Some("a=b"), lscolors.end_code());
+        let style = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+
+        // Round-trips through the (escaped) string representation
+        let reparsed = LsColors::from_string(&lscolors.to_string());
+        assert_eq!(Some("a=b"), reparsed.end_code());
+
+        // Not a valid style, so it is ignored
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("*.foo==31:zz=31=32");
+        assert_eq!(None, lscolors.suffix_style_for_name("a.foo"));
+        assert_eq!(None, lscolors.style_for_custom("zz"));
+    }
+
+    #[test]
+    fn empty_entries() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("::di=34::=31:");
+        assert_eq!(1, lscolors.iter().count());
+        assert_eq!(None, lscolors.style_for_custom(""));
+
+        // Like in `ls`, an empty suffix matches every file name
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("*=31:*.foo=32");
+        let style = |name| {
+            lscolors
+                .suffix_style_for_name(name)
+                .unwrap()
+                .foreground
+                .clone()
+        };
+        assert_eq!(Some(Color::Red), style("file"));
+        assert_eq!(Some(Color::Red), style(""));
+        assert_eq!(Some(Color::Green), style("file.foo"));
+    }
+
+    #[test]
+    fn escaped_suffixes() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("*.a\\:b=31:*.c\\=d=32:*.e\\072f=33:*\\\\g=\\x33\\x34:di=34");
+
+        let style = |name| {
+            lscolors
+                .suffix_style_for_name(name)
+                .unwrap()
+                .foreground
+                .clone()
+        };
+        assert_eq!(Some(Color::Red), style("file.a:b"));
+        assert_eq!(Some(Color::Green), style("file.c=d"));
+        assert_eq!(Some(Color::Yellow), style("file.e:f"));
+        assert_eq!(Some(Color::Blue), style("file\\g"));
+        assert_eq!(
+            Some(Color::Blue),
+            lscolors
+                .style_for_indicator(Indicator::Directory)
+                .unwrap()
+                .foreground
+        );
+
+        // Round-trips through the (escaped) string representation
+        let mut reparsed = LsColors::empty();
+        reparsed.add_from_string(&lscolors.to_string());
+        assert_eq!(lscolors.suffix_mapping, reparsed.suffix_mapping);
+
+        #[cfg(unix)]
+        {
+            let tmp_dir = temp_dir();
+            let path = create_file(tmp_dir.path().join("file.a:b"));
+            let style = lscolors.style_for_path(&path).unwrap();
+            assert_eq!(Some(Color::Red), style.foreground);
+        }
+    }
+
+    #[test]
+    fn left_right_and_end_codes() {
+        let lscolors = LsColors::from_string("*.zip=31");
+        assert_eq!("\x1b[", lscolors.left_code());
+        assert_eq!("m", lscolors.right_code());
+        assert_eq!(None, lscolors.end_code());
+
+        let style = lscolors.style_for_path("file.zip");
+        let painted = lscolors.paint("file.zip", style).to_string();
+        assert_eq!("\x1b[31mfile.zip\x1b[0m", painted);
+
+        let lscolors = LsColors::from_string("lc=\\e[0;:rc=m:ec=\\e[m:*.zip=31");
+        assert_eq!("\x1b[0;", lscolors.left_code());
+        assert_eq!(Some("\x1b[m"), lscolors.end_code());
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::LeftCode));
+
+        let style = lscolors.style_for_path("file.zip");
+        let painted = lscolors.paint("file.zip", style).to_string();
+        assert_eq!("\x1b[0;31mfile.zip\x1b[m", painted);
+
+        let string = lscolors.to_string();
+        assert!(string.contains("lc=\\e[0;:rc=m:ec=\\e[m:"));
+        assert_eq!(Some("\x1b[m"), LsColors::from_string(&string).end_code());
+    }
+
+    #[test]
+    fn suffix_style_for_name() {
+        let lscolors = LsColors::from_string("di=32");
+
+        let style = lscolors.suffix_style_for_name("archive.zip").unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        assert_eq!(None, lscolors.suffix_style_for_name("plain"));
+        assert_eq!(None, lscolors.suffix_style_for_name("."));
+    }
+
+    #[test]
+    fn merge() {
+        let mut lscolors = LsColors::default();
+        let mut overrides = LsColors::empty();
+        overrides.add_from_string("di=33:*.foo=32");
+        lscolors.merge(&overrides);
+
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Yellow), style_dir.foreground);
+
+        let style_zip = lscolors.style_for_path("archive.zip").unwrap();
+        assert_eq!(Some(Color::Red), style_zip.foreground);
+
+        let style_foo = lscolors.style_for_path("file.foo").unwrap();
+        assert_eq!(Some(Color::Green), style_foo.foreground);
+    }
+
+    #[test]
+    fn paint() {
+        let lscolors = LsColors::empty();
+        assert_eq!("plain", lscolors.paint("plain", None).to_string());
+    }
+
+    #[test]
+    fn diff() {
+        let default = LsColors::default();
+        let custom = LsColors::from_string("di=35:*.foo=32:*.zip=01;31");
+
+        let diff = default.diff(&custom);
+        let old = default.style_for_indicator(Indicator::Directory).unwrap();
+        let new = custom.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(
+            vec![(
+                Rule::Indicator(Indicator::Directory, old),
+                Rule::Indicator(Indicator::Directory, new)
+            )],
+            diff.changed
+        );
+        assert_eq!(
+            vec![Rule::Suffix(
+                ".foo",
+                custom.suffix_style_for_name("a.foo").unwrap()
+            )],
+            diff.added
+        );
+        assert!(diff.removed.is_empty());
+
+        let diff = custom.diff(&default);
+        assert_eq!(1, diff.changed.len());
+        assert!(diff.added.is_empty());
+        assert_eq!(1, diff.removed.len());
+
+        assert!(default.diff(&LsColors::default()).is_empty());
+        assert_eq!(LsColorsDiff::default(), default.diff(&default));
+    }
+
+    #[test]
+    fn diff_ignores_suffix_case() {
+        let parse = |input| {
+            let mut lscolors = LsColors::empty();
+            lscolors.add_from_string(input);
+            lscolors
+        };
+        let lower = parse("*.zip=31:*.png=35");
+        let upper = parse("*.ZIP=31:*.Png=36");
+
+        let diff = lower.diff(&upper);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(
+            vec![(
+                Rule::Suffix(".png", lower.suffix_style_for_name("a.png").unwrap()),
+                Rule::Suffix(".png", upper.suffix_style_for_name("a.png").unwrap())
+            )],
+            diff.changed
+        );
+
+        // Within one instance, the last declaration wins regardless of case
+        let mixed = parse("*.zip=31:*.ZIP=32:*.png=35");
+        let diff = lower.diff(&mixed);
+        assert_eq!(1, diff.changed.len());
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+
+        // Case-sensitive instances compare suffixes exactly
+        let upper = upper.with_case_sensitive(true);
+        let diff = lower.diff(&upper);
+        assert_eq!(2, diff.added.len());
+        assert_eq!(2, diff.removed.len());
+    }
+
+    #[test]
+    fn iterate_indicators_and_suffixes() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("di=34:ln=35:ex=0:*.png=36:*.md=33");
+
+        let indicators: Vec<_> = lscolors.indicators().collect();
+        assert_eq!(2, indicators.len());
+        assert_eq!(Indicator::Directory, indicators[0].0);
+        assert_eq!(Some(Color::Blue), indicators[0].1.foreground);
+        assert_eq!(Indicator::SymbolicLink, indicators[1].0);
+
+        let suffixes: Vec<_> = lscolors.suffixes().collect();
+        assert_eq!(2, suffixes.len());
+        assert_eq!(".png", suffixes[0].0);
+        assert_eq!(".md", suffixes[1].0);
+        assert_eq!(Some(Color::Yellow), suffixes[1].1.foreground);
+    }
+
+    #[test]
+    fn indicator_to_str() {
+        for &indicator in Indicator::all() {
+            assert_eq!(Some(indicator), Indicator::from(indicator.to_str()));
+        }
 
-                    Indicator::StickyAndOtherWritable
-                    | Indicator::OtherWritable
-                    | Indicator::Sticky => Indicator::Directory,
+        let mut indicators = Indicator::all().to_vec();
+        indicators.sort();
+        indicators.dedup();
+        assert_eq!(Indicator::all().len(), indicators.len());
+        assert_eq!(Some(&Indicator::ReadOnly), indicators.last());
 
-                    Indicator::OrphanedSymbolicLink => Indicator::SymbolicLink,
+        assert_eq!("di", Indicator::Directory.to_str());
+    }
 
-                    Indicator::MissingFile => Indicator::OrphanedSymbolicLink,
+    #[test]
+    fn no_color_and_clicolor_force() {
+        use std::ffi::OsStr;
+
+        let empty = Some(OsStr::new(""));
+        let zero = Some(OsStr::new("0"));
+        let one = Some(OsStr::new("1"));
+
+        assert!(colors_enabled(None, None));
+        assert!(colors_enabled(empty, None));
+        assert!(!colors_enabled(one, None));
+        assert!(!colors_enabled(one, empty));
+        assert!(!colors_enabled(one, zero));
+        assert!(colors_enabled(one, one));
+        assert!(colors_enabled(None, one));
+    }
 
-                    _ => indicator,
-                })
-            })
-            .or_else(|| self.indicator_mapping.get(&Indicator::Normal))
+    #[test]
+    fn colorterm() {
+        assert!(supports_truecolor(Some(OsStr::new("truecolor"))));
+        assert!(supports_truecolor(Some(OsStr::new("24bit"))));
+        assert!(!supports_truecolor(Some(OsStr::new(""))));
+        assert!(!supports_truecolor(Some(OsStr::new("yes"))));
+        assert!(!supports_truecolor(None));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::style::{Color, FontStyle, Style};
-    use crate::{Indicator, LsColors};
+    #[test]
+    fn downgraded() {
+        let lscolors =
+            LsColors::from_string("di=1;38;2;255;0;0:*.foo=48;2;0;0;0:*.bar=31:uu=58;2;1;2;3");
+        let downgraded = lscolors.downgraded();
 
-    use std::fs::{self, File};
-    use std::path::{Path, PathBuf};
+        let style_dir = downgraded
+            .style_for_indicator(Indicator::Directory)
+            .unwrap();
+        assert_eq!(Some(Color::Fixed(196)), style_dir.foreground);
+        assert_eq!(FontStyle::bold(), style_dir.font_style);
+        let style_foo = downgraded.suffix_style_for_name("a.foo").unwrap();
+        assert_eq!(Some(Color::Fixed(16)), style_foo.background);
+        let style_uu = downgraded.style_for_custom("uu").unwrap();
+        assert_eq!(Some(Color::Fixed(16)), style_uu.underline_color);
+
+        // Styles without 24-bit colors are kept as they are
+        assert!(std::ptr::eq(
+            lscolors.suffix_style_for_name("a.bar").unwrap(),
+            downgraded.suffix_style_for_name("a.bar").unwrap()
+        ));
+    }
 
     #[test]
-    fn basic_usage() {
-        let lscolors = LsColors::default();
+    fn from_env() {
+        // This is the only test that modifies these environment variables
+        std::env::remove_var("LS_COLORS");
+        assert!(LsColors::from_env().is_none());
+        let lscolors = LsColors::from_env_or_default();
+        assert_eq!(
+            LsColors::default().style_for_indicator(Indicator::Directory),
+            lscolors.style_for_indicator(Indicator::Directory)
+        );
+
+        std::env::set_var("LS_COLORS", "");
+        let lscolors = LsColors::from_env().unwrap();
+        assert_eq!(
+            LsColors::default().style_for_indicator(Indicator::Directory),
+            lscolors.style_for_indicator(Indicator::Directory)
+        );
+
+        // The value is layered on top of the defaults
+        std::env::set_var("LS_COLORS", "di=35");
+        for lscolors in &[
+            LsColors::from_env().unwrap(),
+            LsColors::from_env_or_default(),
+        ] {
+            let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+            assert_eq!(Some(Color::Magenta), style_dir.foreground);
+            assert_eq!(
+                LsColors::default().style_for_path("archive.zip"),
+                lscolors.style_for_path("archive.zip")
+            );
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+
+            std::env::set_var("LS_COLORS", OsStr::from_bytes(b"di=35:*.\xff=31"));
+            assert!(LsColors::from_env().is_none());
+            let lscolors = LsColors::from_env_lossy().unwrap();
+            let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+            assert_eq!(Some(Color::Magenta), style_dir.foreground);
+            std::env::set_var("LS_COLORS", "di=35");
+        }
+
+        std::env::set_var("NO_COLOR", "1");
+        std::env::remove_var("CLICOLOR_FORCE");
+        assert!(LsColors::from_env_with_overrides().is_none());
+
+        std::env::set_var("CLICOLOR_FORCE", "1");
+        let lscolors = LsColors::from_env_with_overrides().unwrap();
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Magenta), style_dir.foreground);
+
+        std::env::remove_var("NO_COLOR");
+        std::env::remove_var("CLICOLOR_FORCE");
+        std::env::remove_var("LS_COLORS");
+
+        let lscolors = LsColors::from_string("di=38;2;255;0;0");
+        std::env::remove_var("COLORTERM");
+        let style_dir = lscolors
+            .downgraded_for_terminal()
+            .style_for_indicator(Indicator::Directory)
+            .cloned();
+        assert_eq!(Some(Color::Fixed(196)), style_dir.unwrap().foreground);
+
+        std::env::set_var("COLORTERM", "truecolor");
+        let style_dir = lscolors
+            .downgraded_for_terminal()
+            .style_for_indicator(Indicator::Directory)
+            .cloned();
+        assert_eq!(Some(Color::RGB(255, 0, 0)), style_dir.unwrap().foreground);
+        std::env::remove_var("COLORTERM");
+    }
+
+    #[test]
+    fn from_reader() {
+        let tmp_dir = temp_dir();
+        let path = tmp_dir.path().join("ls_colors");
+        fs::write(&path, "di=34\n").unwrap();
+
+        let lscolors = LsColors::from_reader(File::open(&path).unwrap()).unwrap();
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Blue), style_dir.foreground);
+        assert_eq!(FontStyle::default(), style_dir.font_style);
+
+        let lscolors = LsColors::from_reader("  *.foo=32:ln=35\n\n".as_bytes()).unwrap();
+        let style_foo = lscolors.style_for_path("file.foo").unwrap();
+        assert_eq!(Some(Color::Green), style_foo.foreground);
+        let style_ln = lscolors
+            .style_for_indicator(Indicator::SymbolicLink)
+            .unwrap();
+        assert_eq!(Some(Color::Magenta), style_ln.foreground);
+    }
+
+    #[test]
+    fn from_dircolors_reader() {
+        let database = "\
+# comment
+DIR 01;34
+
+.tar 01;31
+*.gz 00;32
+";
+        let lscolors = LsColors::from_dircolors_reader(database.as_bytes()).unwrap();
 
         let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
-        assert_eq!(FontStyle::bold(), style_dir.font_style);
         assert_eq!(Some(Color::Blue), style_dir.foreground);
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::SymbolicLink));
+
+        let style_tar = lscolors.style_for_path("archive.tar").unwrap();
+        assert_eq!(Some(Color::Red), style_tar.foreground);
+
+        let style_gz = lscolors.style_for_path("archive.gz").unwrap();
+        assert_eq!(Some(Color::Green), style_gz.foreground);
+    }
+
+    #[test]
+    fn extend_from_string() {
+        let mut lscolors = LsColors::empty();
+        lscolors.extend_from_string("di=34:*.foo=32");
+        lscolors.extend_from_string("di=35");
+
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Magenta), style_dir.foreground);
+        let style_foo = lscolors.style_for_path("file.foo").unwrap();
+        assert_eq!(Some(Color::Green), style_foo.foreground);
+
+        // No default styles are added
+        assert_eq!(None, lscolors.style_for_path("file.zip"));
+    }
+
+    #[test]
+    fn from_pairs() {
+        let pairs = vec![
+            ("di".to_string(), "01;33".to_string()),
+            ("*.foo".to_string(), "32".to_string()),
+            ("uu".to_string(), "36".to_string()),
+            ("lc".to_string(), "\\e[".to_string()),
+        ];
+        let lscolors = LsColors::from_pairs(pairs);
+
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Yellow), style_dir.foreground);
+        let style_foo = lscolors.style_for_path("file.foo").unwrap();
+        assert_eq!(Some(Color::Green), style_foo.foreground);
+        let style_uu = lscolors.style_for_custom("uu").unwrap();
+        assert_eq!(Some(Color::Cyan), style_uu.foreground);
+        assert_eq!("\x1b[", lscolors.left_code());
+
+        // Based on the default styles
+        let style_zip = lscolors.style_for_path("file.zip").unwrap();
+        assert_eq!(Some(Color::Red), style_zip.foreground);
+
+        let lscolors = LsColors::from_pairs(vec![("ln", "target")]);
+        let expected = LsColors::from_string("ln=target");
+        assert_eq!(expected.to_string(), lscolors.to_string());
+    }
+
+    #[test]
+    fn from_bsd_string() {
+        let lscolors = LsColors::from_bsd_string("GxFxCxDxBxegedabagacad");
+
+        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
+        assert_eq!(Some(Color::Cyan), style_dir.foreground);
         assert_eq!(None, style_dir.background);
+        assert_eq!(FontStyle::bold(), style_dir.font_style);
 
         let style_symlink = lscolors
             .style_for_indicator(Indicator::SymbolicLink)
             .unwrap();
+        assert_eq!(Some(Color::Magenta), style_symlink.foreground);
         assert_eq!(FontStyle::bold(), style_symlink.font_style);
-        assert_eq!(Some(Color::Cyan), style_symlink.foreground);
-        assert_eq!(None, style_symlink.background);
 
-        let style_rs = lscolors.style_for_path("test.wav").unwrap();
-        assert_eq!(FontStyle::default(), style_rs.font_style);
-        assert_eq!(Some(Color::Cyan), style_rs.foreground);
-        assert_eq!(None, style_rs.background);
+        let style_ow = lscolors
+            .style_for_indicator(Indicator::OtherWritable)
+            .unwrap();
+        assert_eq!(Some(Color::Black), style_ow.foreground);
+        assert_eq!(Some(Color::Yellow), style_ow.background);
+
+        // No suffixes or other default styles
+        assert_eq!(0, lscolors.suffixes().count());
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::RegularFile));
+
+        // Short values leave the remaining indicators unset
+        let lscolors = LsColors::from_bsd_string("ex");
+        assert!(lscolors.style_for_indicator(Indicator::Directory).is_some());
+        assert_eq!(None, lscolors.style_for_indicator(Indicator::SymbolicLink));
     }
 
     #[test]
-    fn style_for_path_uses_correct_ordering() {
-        let lscolors = LsColors::from_string("*.foo=01;35:*README.foo=33;44");
+    fn to_string_round_trip() {
+        let lscolors = LsColors::default();
 
-        let style_foo = lscolors.style_for_path("some/folder/dummy.foo").unwrap();
-        assert_eq!(FontStyle::bold(), style_foo.font_style);
-        assert_eq!(Some(Color::Magenta), style_foo.foreground);
-        assert_eq!(None, style_foo.background);
+        let mut parsed = LsColors::empty();
+        parsed.add_from_string(&lscolors.to_string());
 
-        let style_readme = lscolors
-            .style_for_path("some/other/folder/README.foo")
-            .unwrap();
-        assert_eq!(FontStyle::default(), style_readme.font_style);
-        assert_eq!(Some(Color::Yellow), style_readme.foreground);
-        assert_eq!(Some(Color::Blue), style_readme.background);
+        assert_eq!(lscolors.indicator_mapping, parsed.indicator_mapping);
+        assert_eq!(lscolors.suffix_mapping, parsed.suffix_mapping);
+
+        let lscolors =
+            LsColors::from_string("di=01;38;5;115:*.foo=04;48;2;1;2;3:*README.foo=33;44");
+        assert!(lscolors
+            .to_string()
+            .ends_with("*.foo=4;48;2;1;2;3:*README.foo=33;44:"));
     }
 
     #[test]
-    fn style_for_path_uses_lowercase_matching() {
-        let lscolors = LsColors::from_string("*.O=01;35");
+    fn to_string_round_trip_reset_styles() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("fi=31:di=0:ex=1;22:*.foo=1;22:*.bar=0:xx=00");
+        lscolors.set_indicator(Indicator::Normal, Style::default());
 
-        let style_artifact = lscolors.style_for_path("artifact.o").unwrap();
-        assert_eq!(FontStyle::bold(), style_artifact.font_style);
-        assert_eq!(Some(Color::Magenta), style_artifact.foreground);
-        assert_eq!(None, style_artifact.background);
+        let string = lscolors.to_string();
+        assert_eq!("fi=31:di=0:*.foo=0:*.bar=0:xx=0:", string);
+
+        let mut parsed = LsColors::empty();
+        parsed.add_from_string(&string);
+
+        assert_eq!(lscolors.suffix_mapping, parsed.suffix_mapping);
+        assert_eq!(lscolors.extra_mapping, parsed.extra_mapping);
+        assert_eq!(
+            Some(&Style::default()),
+            parsed.style_for_path_with_metadata("x.foo", None)
+        );
+        assert_eq!(
+            Some(&Style::default()),
+            parsed.style_for_path_with_metadata("x.bar", None)
+        );
+        assert_eq!(
+            Some(&Style::default()),
+            parsed.style_for_indicator(Indicator::Directory)
+        );
+
+        // Like `ex=0`, the reset indicators are left out and fall back to `fi`
+        assert_eq!(
+            None,
+            parsed.indicator_mapping.get(&Indicator::ExecutableFile)
+        );
+        assert_eq!(
+            parsed.style_for_indicator(Indicator::RegularFile),
+            parsed.style_for_indicator(Indicator::ExecutableFile)
+        );
     }
 
     #[test]
-    fn default_styles_should_be_preserved() {
-        // Setting an unrelated style should not influence the default
-        // style for "directory" (below)
-        let lscolors = LsColors::from_string("ex=01:");
+    fn to_string_is_deterministic() {
+        let input = "ex=01;32:uu=33:di=01;34:*.zip=31:sn=32:lc=\\e[:ln=target:fi=0:ow=34;42";
+        let first = LsColors::from_string(input).to_string();
+        for _ in 0..10 {
+            assert_eq!(first, LsColors::from_string(input).to_string());
+        }
 
-        let style_dir = lscolors.style_for_indicator(Indicator::Directory).unwrap();
-        assert_eq!(FontStyle::bold(), style_dir.font_style);
-        assert_eq!(Some(Color::Blue), style_dir.foreground);
-        assert_eq!(None, style_dir.background);
+        let mut indicators = INDICATORS.to_vec();
+        indicators.sort();
+        assert_eq!(Some(&Indicator::Normal), indicators.first());
+        assert_eq!(Some(&Indicator::ReadOnly), indicators.last());
+        assert!(Indicator::RegularFile < Indicator::Directory);
     }
 
     fn temp_dir() -> tempfile::TempDir {
@@ -517,6 +2944,97 @@ mod tests {
         assert_eq!(None, style);
     }
 
+    #[test]
+    fn style_for_file_with_normal_and_file_styles() {
+        let tmp_dir = temp_dir();
+        let tmp_file_path = create_file(tmp_dir.path().join("test-file"));
+
+        // The file name as printed by `ls --color=always` (GNU coreutils 9.1). Text before the
+        // file name, like the `no` style for the rest of the line, is not included.
+        let cases = [
+            ("", "test-file"),
+            ("no=32", "\x1b[32mtest-file\x1b[0m"),
+            ("fi=33", "\x1b[33mtest-file\x1b[0m"),
+            ("no=32:fi=33", "\x1b[33mtest-file\x1b[0m"),
+            ("no=32:fi=0", "\x1b[0mtest-file\x1b[0m"),
+            // `ls` prints the equivalent `\x1b[00m` and `\x1b[m` here
+            ("no=32:fi=00", "\x1b[0mtest-file\x1b[0m"),
+            ("fi=", "\x1b[0mtest-file\x1b[0m"),
+            ("no=32:fi=", "\x1b[0mtest-file\x1b[0m"),
+        ];
+
+        for (ls_colors, expected) in cases.iter() {
+            let mut lscolors = LsColors::empty();
+            lscolors.add_from_string(ls_colors);
+            let style = lscolors.style_for_path(&tmp_file_path);
+            assert_eq!(
+                *expected,
+                lscolors.paint("test-file", style).to_string(),
+                "{}",
+                ls_colors
+            );
+        }
+
+        // Modifiers with an empty style are ignored instead
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("no=32:mh=00");
+        assert_eq!(
+            Some(Color::Green),
+            lscolors
+                .style_for_indicator(Indicator::MultipleHardLinks)
+                .and_then(|style| style.foreground.clone())
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn style_for_hidden_and_read_only_files() {
+        let tmp_dir = temp_dir();
+        let hidden_path = create_file(tmp_dir.path().join("hidden.zip"));
+        let status = std::process::Command::new("attrib")
+            .arg("+h")
+            .arg(&hidden_path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let read_only_path = create_file(tmp_dir.path().join("read-only.txt"));
+        let mut permissions = fs::metadata(&read_only_path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&read_only_path, permissions).unwrap();
+
+        let lscolors = LsColors::from_string("hi=02;37:ro=33");
+
+        let style = lscolors.style_for_path(&hidden_path).unwrap();
+        assert_eq!(Some(Color::White), style.foreground);
+        assert_eq!(FontStyle::dimmed(), style.font_style);
+
+        let style = lscolors.style_for_path(&read_only_path).unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+    }
+
+    #[test]
+    fn write_path_components() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("*.zip=31");
+
+        let mut out = Vec::new();
+        let path = Path::new("missing").join("file.zip");
+        lscolors.write_path_components(&path, &mut out).unwrap();
+        let expected = format!("missing{}\x1b[31mfile.zip\x1b[0m", MAIN_SEPARATOR);
+        assert_eq!(expected.as_bytes(), out.as_slice());
+
+        lscolors.add_from_string("no=33");
+
+        let mut out = Vec::new();
+        lscolors.write_path_components(&path, &mut out).unwrap();
+        let expected = format!(
+            "\x1b[33mmissing{}\x1b[0m\x1b[31mfile.zip\x1b[0m",
+            MAIN_SEPARATOR
+        );
+        assert_eq!(expected.as_bytes(), out.as_slice());
+    }
+
     #[test]
     fn style_for_symlink() {
         let tmp_dir = temp_dir();
@@ -529,6 +3047,106 @@ mod tests {
         assert_eq!(Some(Color::Cyan), style.foreground);
     }
 
+    #[test]
+    fn style_for_path_following_symlinks() {
+        let tmp_dir = temp_dir();
+        let tmp_subdir = create_dir(tmp_dir.path().join("subdir"));
+        let tmp_symlink_path = tmp_dir.path().join("test-symlink");
+        let tmp_broken_symlink_path = tmp_dir.path().join("broken-symlink");
+        create_symlink(&tmp_subdir, &tmp_symlink_path);
+        create_symlink(
+            &tmp_dir.path().join("non-existing"),
+            &tmp_broken_symlink_path,
+        );
+
+        let lscolors = LsColors::default();
+
+        let style = lscolors.style_for_path(&tmp_symlink_path).unwrap();
+        assert_eq!(Some(Color::Cyan), style.foreground);
+        let style = lscolors
+            .style_for_path_following_symlinks(&tmp_symlink_path)
+            .unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+
+        let style = lscolors
+            .style_for_path_following_symlinks(&tmp_broken_symlink_path)
+            .unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn style_for_path_async() {
+        let tmp_dir = temp_dir();
+        let tmp_file = create_file(tmp_dir.path().join("archive.zip"));
+        let tmp_subdir = create_dir(tmp_dir.path().join("subdir"));
+        let tmp_broken_symlink_path = tmp_dir.path().join("broken-symlink");
+        create_symlink(
+            &tmp_dir.path().join("non-existing"),
+            &tmp_broken_symlink_path,
+        );
+
+        let lscolors = LsColors::default();
+
+        let style = lscolors.style_for_path_async(&tmp_file).await.unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+
+        let style = lscolors.style_for_path_async(&tmp_subdir).await.unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+
+        let style = lscolors
+            .style_for_path_async(&tmp_broken_symlink_path)
+            .await
+            .unwrap();
+        assert_eq!(
+            lscolors.style_for_path(&tmp_broken_symlink_path),
+            Some(style)
+        );
+    }
+
+    #[test]
+    fn style_for_symlink_as_target() {
+        let tmp_dir = temp_dir();
+        let tmp_file_path = create_file(tmp_dir.path().join("test-file.png"));
+        let tmp_symlink_path = tmp_dir.path().join("test-symlink");
+        let tmp_broken_symlink_path = tmp_dir.path().join("broken-symlink.png");
+        create_symlink(&tmp_file_path, &tmp_symlink_path);
+        create_symlink(
+            &tmp_dir.path().join("non-existing"),
+            &tmp_broken_symlink_path,
+        );
+
+        let lscolors = LsColors::from_string("ln=target:or=31:*.png=35");
+        assert!(lscolors.to_string().contains("ln=target:"));
+
+        let style = lscolors.style_for_path(&tmp_symlink_path).unwrap();
+        assert_eq!(Some(Color::Magenta), style.foreground);
+
+        let style = lscolors.style_for_path(&tmp_broken_symlink_path).unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+
+        let lscolors = LsColors::from_string("ln=target:ln=36:*.png=35");
+        let style = lscolors.style_for_path(&tmp_symlink_path).unwrap();
+        assert_eq!(Some(Color::Cyan), style.foreground);
+
+        // Without a style for `or`, broken links are still orphans, and use the `or` fallback
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("ln=target:no=33:mi=34:*.png=35");
+        assert_eq!(
+            Indicator::OrphanedSymbolicLink,
+            lscolors.indicator_for_path(
+                &tmp_broken_symlink_path,
+                tmp_broken_symlink_path.symlink_metadata().ok().as_ref()
+            )
+        );
+        let style = lscolors.style_for_path(&tmp_broken_symlink_path).unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+
+        lscolors.set_fallback(Indicator::OrphanedSymbolicLink, Indicator::MissingFile);
+        let style = lscolors.style_for_path(&tmp_broken_symlink_path).unwrap();
+        assert_eq!(Some(Color::Blue), style.foreground);
+    }
+
     #[test]
     fn style_for_broken_symlink() {
         let tmp_dir = temp_dir();
@@ -541,28 +3159,175 @@ mod tests {
         assert_eq!(Some(Color::Red), style.foreground);
     }
 
+    #[test]
+    fn styles_for_paths() {
+        let tmp_dir = temp_dir();
+        let dir = create_dir(tmp_dir.path().join("dir"));
+        let zip = create_file(tmp_dir.path().join("archive.zip"));
+        let missing = tmp_dir.path().join("missing.png");
+
+        let items: Vec<_> = [&dir, &zip, &missing]
+            .iter()
+            .map(|path| (path.to_path_buf(), path.symlink_metadata().ok()))
+            .collect();
+
+        let lscolors = LsColors::from_string("di=34:*.zip=31:*.png=35");
+        let styles: Vec<_> = lscolors
+            .styles_for_paths(items.iter().map(|(path, md)| (path.as_path(), md.as_ref())))
+            .map(|style| style.and_then(|style| style.foreground.clone()))
+            .collect();
+        assert_eq!(
+            vec![Some(Color::Blue), Some(Color::Red), Some(Color::Magenta)],
+            styles
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_styles_for_paths() {
+        let tmp_dir = temp_dir();
+        let mut paths = vec![tmp_dir.path().join("missing.png")];
+        for i in 0..10 {
+            let dir = create_dir(tmp_dir.path().join(format!("dir{}", i)));
+            for name in &["archive.zip", "image.png", "README"] {
+                paths.push(create_file(dir.join(name)));
+            }
+            paths.push(dir);
+        }
+
+        let lscolors = LsColors::default();
+        let sequential: Vec<_> = paths
+            .iter()
+            .map(|path| lscolors.style_for_path_owned(path))
+            .collect();
+        assert_eq!(sequential, lscolors.par_styles_for_paths(&paths));
+    }
+
+    #[test]
+    fn style_for_symlink_with_target_metadata() {
+        let tmp_dir = temp_dir();
+        let tmp_file_path = create_file(tmp_dir.path().join("test-file"));
+        let tmp_symlink_path = tmp_dir.path().join("test-symlink");
+        create_symlink(&tmp_file_path, &tmp_symlink_path);
+
+        let lscolors = LsColors::default();
+        let metadata = tmp_symlink_path.symlink_metadata().ok();
+        let target_metadata = tmp_symlink_path.metadata().ok();
+
+        let style = lscolors
+            .style_for_path_with_metadata_and_target(
+                &tmp_symlink_path,
+                metadata.as_ref(),
+                target_metadata.as_ref(),
+            )
+            .unwrap();
+        assert_eq!(Some(Color::Cyan), style.foreground);
+
+        let style = lscolors
+            .style_for_path_with_metadata_and_target(&tmp_symlink_path, metadata.as_ref(), None)
+            .unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+    }
+
     #[test]
     fn style_for_missing_file() {
         let lscolors1 = LsColors::from_string("mi=01:or=33;44");
 
-        let style_missing = lscolors1
-            .style_for_indicator(Indicator::MissingFile)
+        let style_missing = lscolors1
+            .style_for_indicator(Indicator::MissingFile)
+            .unwrap();
+        assert_eq!(FontStyle::bold(), style_missing.font_style);
+
+        let lscolors2 = LsColors::from_string("or=33;44");
+
+        let style_missing = lscolors2
+            .style_for_indicator(Indicator::MissingFile)
+            .unwrap();
+        assert_eq!(Some(Color::Yellow), style_missing.foreground);
+
+        let lscolors3 = LsColors::from_string("or=33;44:mi=00");
+
+        let style_missing = lscolors3
+            .style_for_indicator(Indicator::MissingFile)
+            .unwrap();
+        assert_eq!(Some(Color::Yellow), style_missing.foreground);
+    }
+
+    #[test]
+    fn style_for_nonexistent_path() {
+        let tmp_dir = temp_dir();
+        let missing = tmp_dir.path().join("missing.zip");
+
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("mi=01;31:*.zip=32");
+        let (style, reason) = lscolors.style_for_path_with_reason(&missing).unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        assert_eq!(MatchReason::Indicator(Indicator::MissingFile), reason);
+        assert_eq!(Some(style), lscolors.style_for_path(&missing));
+        assert_eq!(
+            Some(style),
+            lscolors.style_for_path_following_symlinks(&missing)
+        );
+        assert_eq!(
+            Some(style),
+            lscolors.style_for_path_cached(&mut StyleCache::new(), &missing)
+        );
+
+        // Without metadata, the path is still treated as a regular file
+        let style = lscolors
+            .style_for_path_with_metadata(&missing, None)
             .unwrap();
-        assert_eq!(FontStyle::bold(), style_missing.font_style);
+        assert_eq!(Some(Color::Green), style.foreground);
 
-        let lscolors2 = LsColors::from_string("or=33;44");
+        // Without a style for `mi` (like in the defaults), suffixes apply
+        let lscolors = LsColors::default();
+        assert_eq!(
+            lscolors.suffix_style_for_name("missing.zip"),
+            lscolors.style_for_path(&missing)
+        );
+    }
 
-        let style_missing = lscolors2
-            .style_for_indicator(Indicator::MissingFile)
-            .unwrap();
-        assert_eq!(Some(Color::Yellow), style_missing.foreground);
+    #[test]
+    fn custom_fallback() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("fi=32:or=33;44:ex=31");
+        lscolors.set_fallback(Indicator::MissingFile, Indicator::RegularFile);
+        lscolors.set_fallback(Indicator::Capabilities, Indicator::ExecutableFile);
+
+        let foreground = |lscolors: &LsColors, indicator| {
+            lscolors
+                .style_for_indicator(indicator)
+                .and_then(|style| style.foreground.clone())
+        };
+        assert_eq!(
+            Some(Color::Green),
+            foreground(&lscolors, Indicator::MissingFile)
+        );
+        assert_eq!(
+            Some(Color::Red),
+            foreground(&lscolors, Indicator::Capabilities)
+        );
+        assert_eq!(
+            Some(Color::Yellow),
+            foreground(&lscolors, Indicator::OrphanedSymbolicLink)
+        );
 
-        let lscolors3 = LsColors::from_string("or=33;44:mi=00");
+        // Fallbacks are kept when merging
+        let mut merged = LsColors::empty();
+        merged.add_from_string("fi=35");
+        merged.merge(&lscolors);
+        assert_eq!(
+            Some(Color::Red),
+            foreground(&merged, Indicator::Capabilities)
+        );
 
-        let style_missing = lscolors3
-            .style_for_indicator(Indicator::MissingFile)
-            .unwrap();
-        assert_eq!(Some(Color::Yellow), style_missing.foreground);
+        // A fallback to the indicator itself uses `no`
+        let mut lscolors = LsColors::from_string("no=36:or=33");
+        lscolors.set_fallback(Indicator::MissingFile, Indicator::MissingFile);
+        assert_eq!(
+            Some(Color::Cyan),
+            foreground(&lscolors, Indicator::MissingFile)
+        );
     }
 
     #[cfg(unix)]
@@ -584,6 +3349,76 @@ mod tests {
         assert_eq!(Some(Color::Yellow), sgid_style.background);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn style_for_capabilities() {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let tmp_dir = temp_dir();
+        let tmp_file = create_file(tmp_dir.path().join("cap"));
+
+        let lscolors = LsColors::from_string("ca=30;41");
+        assert_eq!(None, lscolors.style_for_path(&tmp_file));
+
+        // VFS_CAP_REVISION_2 with CAP_NET_RAW in the permitted set
+        let mut cap = [0u8; 20];
+        cap[0..4].copy_from_slice(&0x0200_0000u32.to_le_bytes());
+        cap[4..8].copy_from_slice(&(1u32 << 13).to_le_bytes());
+
+        let path = CString::new(tmp_file.as_os_str().as_bytes()).unwrap();
+        let name = CString::new("security.capability").unwrap();
+        let result = unsafe {
+            libc::setxattr(
+                path.as_ptr(),
+                name.as_ptr(),
+                cap.as_ptr() as *const libc::c_void,
+                cap.len(),
+                0,
+            )
+        };
+        if result != 0 {
+            // Setting capabilities requires privileges
+            return;
+        }
+
+        let style = lscolors.style_for_path(&tmp_file).unwrap();
+        assert_eq!(Some(Color::Red), style.background);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn indicator_for_path() {
+        use std::fs::{set_permissions, Permissions};
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp_dir = temp_dir();
+        let tmp_file = create_file(tmp_dir.path().join("setuid"));
+        set_permissions(&tmp_file, Permissions::from_mode(0o4755)).unwrap();
+
+        let lscolors = LsColors::default();
+
+        let metadata = tmp_dir.path().symlink_metadata().unwrap();
+        assert_eq!(
+            Indicator::Directory,
+            lscolors.indicator_for_path(tmp_dir.path(), Some(&metadata))
+        );
+
+        let metadata = tmp_file.symlink_metadata().unwrap();
+        assert_eq!(
+            Indicator::Setuid,
+            lscolors.indicator_for_path(&tmp_file, Some(&metadata))
+        );
+        assert_eq!(
+            Indicator::RegularFile,
+            LsColors::empty().indicator_for_path(&tmp_file, Some(&metadata))
+        );
+        assert_eq!(
+            Indicator::RegularFile,
+            lscolors.indicator_for_path(&tmp_file, None)
+        );
+    }
+
     #[cfg(unix)]
     #[test]
     fn style_for_multi_hard_links() {
@@ -596,6 +3431,27 @@ mod tests {
         assert_eq!(Some(Color::Magenta), style.foreground);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn style_for_multi_hard_links_with_suffix() {
+        let tmp_dir = temp_dir();
+        let tmp_file = create_file(tmp_dir.path().join("file.foo"));
+        std::fs::hard_link(&tmp_file, tmp_dir.path().join("file2.foo")).unwrap();
+
+        // Like `ls`, `mh` takes precedence by default
+        let lscolors = LsColors::from_string("mh=35:*.foo=31");
+        let style = lscolors.style_for_path(&tmp_file).unwrap();
+        assert_eq!(Some(Color::Magenta), style.foreground);
+
+        let lscolors = lscolors.with_suffixes_on_hard_links(true);
+        let style = lscolors.style_for_path(&tmp_file).unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+
+        let lscolors = LsColors::from_string("mh=35:*.bar=31").with_suffixes_on_hard_links(true);
+        let style = lscolors.style_for_path(&tmp_file).unwrap();
+        assert_eq!(Some(Color::Magenta), style.foreground);
+    }
+
     #[cfg(unix)]
     #[test]
     fn style_for_sticky_other_writable() {
@@ -622,6 +3478,68 @@ mod tests {
         assert_eq!(Some(Color::Blue), st_style.background);
     }
 
+    #[test]
+    fn style_for_dir_entry() {
+        let tmp_root = temp_dir();
+        create_dir(tmp_root.path().join("test-dir"));
+        create_file(tmp_root.path().join("test-file.png"));
+        create_file(tmp_root.path().join("test-file"));
+        create_symlink(
+            &tmp_root.path().join("test-dir"),
+            &tmp_root.path().join("test-symlink"),
+        );
+        create_symlink(
+            &tmp_root.path().join("non-existing"),
+            &tmp_root.path().join("broken-symlink"),
+        );
+
+        for lscolors in &[
+            LsColors::default(),
+            LsColors::from_string("ex=0:tw=0:ow=0:st=0:su=0:sg=0:mh=0"),
+        ] {
+            for entry in fs::read_dir(tmp_root.path()).unwrap() {
+                let entry = entry.unwrap();
+                assert_eq!(
+                    lscolors.style_for_path(entry.path()),
+                    lscolors.style_for_dir_entry(&entry),
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "walkdir")]
+    #[test]
+    fn style_for_walkdir_entry() {
+        let tmp_root = temp_dir();
+        let tmp_dir = create_dir(tmp_root.path().join("test-dir"));
+        create_file(tmp_dir.join("test-file.png"));
+        create_symlink(&tmp_dir, &tmp_root.path().join("test-symlink"));
+        create_symlink(
+            &tmp_root.path().join("non-existing"),
+            &tmp_root.path().join("broken-symlink"),
+        );
+
+        let lscolors = LsColors::from_string("di=34:ln=35:or=31:*.png=36");
+
+        for entry in walkdir::WalkDir::new(tmp_root.path()) {
+            let entry = entry.unwrap();
+            assert_eq!(
+                lscolors.style_for_path(entry.path()),
+                lscolors.style_for_walkdir_entry(&entry),
+            );
+        }
+
+        let followed: Vec<_> = walkdir::WalkDir::new(tmp_root.path())
+            .follow_links(true)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path_is_symlink())
+            .collect();
+        assert_eq!(1, followed.len());
+        let style = lscolors.style_for_walkdir_entry(&followed[0]).unwrap();
+        assert_eq!(Some(Color::Magenta), style.foreground);
+    }
+
     #[test]
     fn style_for_path_components() {
         use std::ffi::OsString;
@@ -657,4 +3575,491 @@ mod tests {
         let (_, style_dir) = components.pop().unwrap();
         assert_eq!(Some(Color::Blue), style_dir.unwrap().foreground);
     }
+
+    #[test]
+    fn style_for_path_components_dot_dirs() {
+        let lscolors = LsColors::from_string("di=34");
+
+        // The `..` after a missing directory is still styled as a directory
+        let components: Vec<_> = lscolors
+            .style_for_path_components(Path::new("../does-not-exist/../x"))
+            .map(|(_, style)| style.and_then(|style| style.foreground.clone()))
+            .collect();
+        assert_eq!(
+            vec![Some(Color::Blue), None, Some(Color::Blue), None],
+            components
+        );
+    }
+
+    #[test]
+    fn regular_file_precedence() {
+        use Indicator::*;
+
+        const ALL: &str = "ca=1:su=2:sg=3:ex=4:mh=5:fi=6";
+        let cases = [
+            // (LS_COLORS, mode, nlink, has capabilities, expected)
+            (ALL, 0o6755, 2, true, Setuid),
+            (ALL, 0o2755, 2, true, Setgid),
+            (ALL, 0o0755, 2, true, Capabilities),
+            (ALL, 0o0644, 1, true, Capabilities),
+            (ALL, 0o0755, 2, false, ExecutableFile),
+            (ALL, 0o0100, 1, false, ExecutableFile),
+            (ALL, 0o0644, 2, false, MultipleHardLinks),
+            (ALL, 0o0644, 1, false, RegularFile),
+            // Indicators without a style are skipped
+            ("ca=1:sg=3:ex=4", 0o4755, 1, false, ExecutableFile),
+            ("ca=1:ex=4", 0o6755, 1, true, Capabilities),
+            ("ex=4:mh=5", 0o6755, 2, true, ExecutableFile),
+            ("mh=5", 0o6755, 2, true, MultipleHardLinks),
+            ("", 0o6755, 2, true, RegularFile),
+        ];
+
+        for (ls_colors, mode, nlink, caps, expected) in cases.iter().cloned() {
+            let mut lscolors = LsColors::empty();
+            lscolors.add_from_string(ls_colors);
+            assert_eq!(
+                expected,
+                lscolors.indicator_for_regular_file(mode, nlink, 0, || caps),
+                "{} {:o} {} {}",
+                ls_colors,
+                mode,
+                nlink,
+                caps
+            );
+        }
+    }
+
+    #[test]
+    fn directory_precedence() {
+        use Indicator::*;
+
+        // The modes of the expected indicators below
+        const MODES: [u32; 4] = [0o0755, 0o1755, 0o0777, 0o1777];
+        let cases = [
+            // (LS_COLORS, expected for plain, sticky, other-writable, sticky + other-writable)
+            ("", [Directory, Directory, Directory, Directory]),
+            (
+                "tw=1",
+                [Directory, Directory, Directory, StickyAndOtherWritable],
+            ),
+            ("ow=2", [Directory, Directory, OtherWritable, OtherWritable]),
+            ("st=3", [Directory, Sticky, Directory, Sticky]),
+            (
+                "tw=1:ow=2",
+                [Directory, Directory, OtherWritable, StickyAndOtherWritable],
+            ),
+            (
+                "tw=1:st=3",
+                [Directory, Sticky, Directory, StickyAndOtherWritable],
+            ),
+            (
+                "ow=2:st=3",
+                [Directory, Sticky, OtherWritable, OtherWritable],
+            ),
+            (
+                "tw=1:ow=2:st=3",
+                [Directory, Sticky, OtherWritable, StickyAndOtherWritable],
+            ),
+        ];
+
+        for (ls_colors, expected) in cases.iter() {
+            let mut lscolors = LsColors::empty();
+            lscolors.add_from_string(ls_colors);
+            for (mode, expected) in MODES.iter().zip(expected.iter()) {
+                assert_eq!(
+                    *expected,
+                    lscolors.indicator_for_directory(*mode),
+                    "{} {:o}",
+                    ls_colors,
+                    mode
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn style_for_path_cached() {
+        let lscolors = LsColors::from_string("*.gz=31:*.tar.gz=32:*.TXT=33:*README=34:*z=35");
+        let mut cache = StyleCache::new();
+
+        let filenames = [
+            "a.gz",
+            "a.tar.gz",
+            "b.GZ",
+            "notes.txt",
+            "NOTES.TXT",
+            "README",
+            "a.README",
+            "a.xz",
+            "archive.zip",
+            "no_extension",
+            "a.rs",
+        ];
+        // Look up every name twice, to check both cache misses and hits
+        for filename in filenames.iter().chain(filenames.iter()) {
+            assert_eq!(
+                lscolors.style_for_path(filename),
+                lscolors.style_for_path_cached(&mut cache, filename),
+                "{}",
+                filename
+            );
+        }
+
+        let lscolors = lscolors.with_case_sensitive(true);
+        let mut cache = StyleCache::new();
+        for filename in filenames.iter().chain(filenames.iter()) {
+            assert_eq!(
+                lscolors.style_for_path(filename),
+                lscolors.style_for_path_cached(&mut cache, filename),
+                "{}",
+                filename
+            );
+        }
+    }
+
+    #[test]
+    fn style_for_path_cached_miss() {
+        let lscolors = LsColors::from_string("fi=36:*.gz=31");
+        let mut cache = StyleCache::new();
+        let style = lscolors.style_for_path_cached(&mut cache, "notes.txt");
+        assert_eq!(lscolors.style_for_indicator(Indicator::RegularFile), style);
+
+        // A cached miss is used as-is, without looking up the suffix again. This is only visible
+        // by (wrongly) reusing the cache for a different instance, which does match `*.txt`.
+        let other = LsColors::from_string("fi=36:*.gz=31:*.txt=33");
+        assert_eq!(
+            other.style_for_indicator(Indicator::RegularFile),
+            other.style_for_path_cached(&mut cache, "other.txt")
+        );
+        assert_ne!(
+            other.style_for_path("other.txt"),
+            other.style_for_path_cached(&mut cache, "other.txt")
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        let lscolors: LsColors = "di=35:*.zip=36".parse().unwrap();
+        let expected = LsColors::from_string("di=35:*.zip=36");
+        assert_eq!(
+            expected.style_for_indicator(Indicator::Directory),
+            lscolors.style_for_indicator(Indicator::Directory)
+        );
+        assert_eq!(
+            expected.style_for_path("archive.zip"),
+            lscolors.style_for_path("archive.zip")
+        );
+    }
+
+    #[test]
+    fn clone_shares_styles() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<LsColors>();
+
+        let mut lscolors = LsColors::default();
+        for i in 0..1000 {
+            lscolors.add_suffix(format!(".ext{}", i), Style::default());
+        }
+        let clone = lscolors.clone();
+
+        assert!(std::ptr::eq(
+            lscolors.style_for_indicator(Indicator::Directory).unwrap(),
+            clone.style_for_indicator(Indicator::Directory).unwrap()
+        ));
+        assert!(std::ptr::eq(
+            lscolors.suffix_style_for_name("a.ext500").unwrap(),
+            clone.suffix_style_for_name("a.ext500").unwrap()
+        ));
+
+        // Merging shares the styles as well
+        let mut merged = LsColors::empty();
+        merged.merge(&lscolors);
+        assert!(std::ptr::eq(
+            lscolors.style_for_indicator(Indicator::Directory).unwrap(),
+            merged.style_for_indicator(Indicator::Directory).unwrap()
+        ));
+    }
+
+    #[test]
+    fn style_for_os_str() {
+        let lscolors = LsColors::from_string("fi=33:*.zip=31");
+
+        assert_eq!(
+            Some(Color::Red),
+            lscolors
+                .style_for_os_str(OsStr::new("a.zip"))
+                .and_then(|style| style.foreground.clone())
+        );
+        assert_eq!(
+            Some(Color::Yellow),
+            lscolors
+                .style_for_os_str(OsStr::new("a.txt"))
+                .and_then(|style| style.foreground.clone())
+        );
+    }
+
+    #[test]
+    fn final_component_style_by_name() {
+        let lscolors = LsColors::from_string("di=34:*.png=35");
+
+        let foreground = |style: Option<&Style>| style.and_then(|style| style.foreground.clone());
+        assert_eq!(
+            Some(Color::Magenta),
+            foreground(lscolors.final_component_style_by_name("missing/b/c.png"))
+        );
+        assert_eq!(
+            Some(Color::Blue),
+            foreground(lscolors.final_component_style_by_name("missing/b/"))
+        );
+        assert_eq!(
+            Some(Color::Blue),
+            foreground(lscolors.final_component_style_by_name("missing/.."))
+        );
+        assert_eq!(
+            Some(Color::Blue),
+            foreground(lscolors.final_component_style_by_name("missing/."))
+        );
+        assert_eq!(
+            Some(Color::Blue),
+            foreground(lscolors.final_component_style_by_name("."))
+        );
+        assert_eq!(
+            Some(Color::Magenta),
+            foreground(lscolors.final_component_style_by_name("missing/.png"))
+        );
+        assert_eq!(None, lscolors.final_component_style_by_name(""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn style_for_os_str_non_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let lscolors = LsColors::from_string("*.zip=31");
+        let name = OsStr::from_bytes(b"\xff\xfe.zip");
+        assert_eq!(
+            Some(Color::Red),
+            lscolors
+                .style_for_os_str(name)
+                .and_then(|style| style.foreground.clone())
+        );
+    }
+
+    #[test]
+    fn suffixes_on_directories() {
+        let tmp_dir = temp_dir();
+        let dir = create_dir(tmp_dir.path().join("old.bak"));
+        let file = create_file(tmp_dir.path().join("file.bak"));
+
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("di=34:*.bak=2;37");
+
+        let foreground = |lscolors: &LsColors, path: &Path| {
+            lscolors
+                .style_for_path(path)
+                .and_then(|style| style.foreground.clone())
+        };
+        assert_eq!(Some(Color::Blue), foreground(&lscolors, &dir));
+        assert_eq!(Some(Color::White), foreground(&lscolors, &file));
+        assert_eq!(
+            Some(Color::Blue),
+            lscolors
+                .style_for_type(FileTypeHint::Directory, "old.bak")
+                .and_then(|style| style.foreground.clone())
+        );
+
+        let lscolors = lscolors.with_suffixes_on_directories(true);
+        assert_eq!(Some(Color::White), foreground(&lscolors, &dir));
+        assert_eq!(Some(Color::White), foreground(&lscolors, &file));
+        assert_eq!(
+            Some(Color::White),
+            lscolors
+                .style_for_type(FileTypeHint::Directory, "old.bak")
+                .and_then(|style| style.foreground.clone())
+        );
+        assert_eq!(
+            Some(Color::White),
+            lscolors
+                .style_for_path_cached(&mut StyleCache::new(), &dir)
+                .and_then(|style| style.foreground.clone())
+        );
+        assert_eq!(Some(Color::Blue), foreground(&lscolors, tmp_dir.path()));
+    }
+
+    #[test]
+    fn longest_match() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("*.tar.gz=31:*.gz=32:*z=33");
+
+        let style = lscolors.style_for_path("x.tar.gz").unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+
+        let lscolors = lscolors.with_longest_match(true);
+        let style = lscolors.style_for_path("x.tar.gz").unwrap();
+        assert_eq!(Some(Color::Red), style.foreground);
+        let style = lscolors.suffix_style_for_name("x.gz").unwrap();
+        assert_eq!(Some(Color::Green), style.foreground);
+        let style = lscolors.suffix_style_for_name("x.xz").unwrap();
+        assert_eq!(Some(Color::Yellow), style.foreground);
+
+        let mut cache = StyleCache::new();
+        for filename in &["x.tar.gz", "x.gz", "x.xz", "x.tar.gz"] {
+            assert_eq!(
+                lscolors.style_for_path(filename),
+                lscolors.style_for_path_cached(&mut cache, filename)
+            );
+        }
+    }
+
+    #[test]
+    fn all_matching_suffixes() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("*.tar.gz=31:*.zip=32:*.GZ=33:*.txt=34");
+
+        let matches = lscolors.all_matching_suffixes("x.tar.gz");
+        assert_eq!(
+            vec![".tar.gz", ".GZ"],
+            matches
+                .iter()
+                .map(|(suffix, _)| *suffix)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            matches.last().map(|(_, style)| *style),
+            lscolors.suffix_style_for_name("x.tar.gz")
+        );
+
+        assert!(lscolors.all_matching_suffixes("README").is_empty());
+
+        let lscolors = lscolors.with_case_sensitive(true);
+        let matches = lscolors.all_matching_suffixes("x.tar.gz");
+        assert_eq!(1, matches.len());
+    }
+
+    #[test]
+    fn painted_components() {
+        let tmp_dir = temp_dir();
+        let dir = tmp_dir.path().join("dir");
+        fs::create_dir(&dir).unwrap();
+        let path = dir.join("file.zip");
+
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("di=34:*.zip=31");
+
+        let painted: String = lscolors.painted_components(&path).collect();
+        let expected = format!(
+            "\x1b[34m{}{}\x1b[0m\x1b[31mfile.zip\x1b[0m",
+            "dir", MAIN_SEPARATOR
+        );
+        assert!(painted.ends_with(&expected), "{:?}", painted);
+        assert_eq!(
+            lscolors.style_for_path_components(&path).count(),
+            lscolors.painted_components(&path).count()
+        );
+    }
+
+    #[test]
+    fn sample_lines() {
+        let lscolors = LsColors::default();
+        let lines = lscolors.sample_lines();
+
+        assert!(lines.contains(&"\x1b[1;34mdi -> directory\x1b[0m".to_string()));
+        assert!(lines.contains(&"\x1b[1;31m*.zip -> archive\x1b[0m".to_string()));
+        assert!(lines.contains(&"\x1b[36m*.flac -> audio\x1b[0m".to_string()));
+        assert_eq!(
+            lscolors.indicators().count() + lscolors.suffixes().count(),
+            lines.len()
+        );
+
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("*.foo=32");
+        assert_eq!(
+            vec!["\x1b[32m*.foo -> file\x1b[0m".to_string()],
+            lscolors.sample_lines()
+        );
+    }
+
+    #[test]
+    fn style_for_path_components_with_separator() {
+        let lscolors = LsColors::empty();
+
+        let path: PathBuf = ["foo", "bar", "baz.txt"].iter().collect();
+        let components: Vec<_> = lscolors
+            .style_for_path_components_with_separator(&path, '/')
+            .map(|(component, _)| component.into_string().unwrap())
+            .collect();
+        assert_eq!(vec!["foo/", "bar/", "baz.txt"], components);
+
+        let path: PathBuf = [MAIN_SEPARATOR.to_string().as_str(), "foo"]
+            .iter()
+            .collect();
+        let components: Vec<_> = lscolors
+            .style_for_path_components_with_separator(&path, '|')
+            .map(|(component, _)| component.into_string().unwrap())
+            .collect();
+        assert_eq!(vec!["|", "foo"], components);
+    }
+
+    #[test]
+    fn style_for_path_components_with_separator_absolute() {
+        let mut lscolors = LsColors::empty();
+        lscolors.add_from_string("di=34:fi=31:mi=35");
+
+        let tmp_root = temp_dir();
+        let path = tmp_root.path().join("test-file");
+        create_file(&path);
+
+        let components: Vec<_> = lscolors
+            .style_for_path_components_with_separator(&path, '|')
+            .collect();
+        let expected: Vec<_> = lscolors.style_for_path_components(&path).collect();
+        assert_eq!(
+            expected.iter().map(|(_, style)| style).collect::<Vec<_>>(),
+            components
+                .iter()
+                .map(|(_, style)| style)
+                .collect::<Vec<_>>()
+        );
+
+        // The root directory is looked up in the file system, not the custom separator
+        let (_, root_style) = components
+            .iter()
+            .find(|(component, _)| component == "|")
+            .unwrap();
+        assert_eq!(
+            lscolors.style_for_indicator(Indicator::Directory),
+            *root_style
+        );
+
+        let (component, style) = components.last().unwrap();
+        assert_eq!("test-file", component);
+        assert_eq!(lscolors.style_for_indicator(Indicator::RegularFile), *style);
+    }
+
+    #[test]
+    fn style_for_path_components_trailing_separator() {
+        let lscolors = LsColors::empty();
+        let separator = MAIN_SEPARATOR.to_string();
+
+        let components: Vec<_> = lscolors
+            .style_for_path_components(Path::new("foo/bar/"))
+            .map(|(component, _)| component.into_string().unwrap())
+            .collect();
+        assert_eq!(
+            vec![format!("foo{}", separator), format!("bar{}", separator)],
+            components
+        );
+
+        let (last, _) = lscolors
+            .style_for_path_components(Path::new("foo/bar"))
+            .last()
+            .unwrap();
+        assert_eq!("bar", last);
+
+        let components: Vec<_> = lscolors
+            .style_for_path_components(Path::new("/"))
+            .map(|(component, _)| component.into_string().unwrap())
+            .collect();
+        assert_eq!(vec![separator], components);
+    }
 }
@@ -2,17 +2,29 @@
 //!
 //! For more information, see
 //! [ANSI escape code (Wikipedia)](https://en.wikipedia.org/wiki/ANSI_escape_code).
-use std::collections::VecDeque;
-
-#[cfg(ansi_term)]
-use ansi_term;
-
-#[cfg(crossterm)]
-use crossterm;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::FromStr;
 
 /// A `Color` can be one of the pre-defined ANSI colors (`Red`, `Green`, ..),
 /// a 8-bit ANSI color (`Fixed(u8)`) or a 24-bit color (`RGB(u8, u8, u8)`).
-#[derive(Debug, Clone, PartialEq)]
+///
+/// The bright variants (`BrightRed`, `BrightGreen`, ..) correspond to the aixterm codes `90`-`97`
+/// (foreground) and `100`-`107` (background). They are kept distinct from `Fixed(8)`-`Fixed(15)`
+/// so that the terminal palette still applies.
+///
+/// `Default` is the terminal's default color, as explicitly selected by `39` (foreground) or `49`
+/// (background). This is different from a `None` color in a [`Style`], which leaves the color
+/// unspecified.
+///
+/// New variants may be added in minor releases.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum Color {
     Black,
     Red,
@@ -32,10 +44,233 @@ pub enum Color {
     BrightWhite,
     Fixed(u8),
     RGB(u8, u8, u8),
+    Default,
 }
 
 impl Color {
-    /// Convert to a `ansi_term::Color` (if the `ansi_term` feature is enabled).
+    /// Create a 24-bit `RGB` color.
+    pub fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color::RGB(r, g, b)
+    }
+
+    /// Create an 8-bit color from the 256-color palette.
+    pub fn fixed(n: u8) -> Color {
+        Color::Fixed(n)
+    }
+
+    /// Get the red, green and blue channels of an `RGB` color. Returns `None` for all other
+    /// colors, whose actual values depend on the terminal; use [`to_rgb`](#method.to_rgb) for an
+    /// approximation.
+    pub fn channels(&self) -> Option<(u8, u8, u8)> {
+        match self {
+            Color::RGB(r, g, b) => Some((*r, *g, *b)),
+            _ => None,
+        }
+    }
+
+    /// Write the SGR parameters for this color (e.g. `34` or `38;5;115`) to `out`.
+    fn write_ansi_sequence(&self, out: &mut String, background: bool) {
+        let (base, bright_base, extended) = if background {
+            (40, 100, 48)
+        } else {
+            (30, 90, 38)
+        };
+
+        let _ = match self {
+            Color::Black => write!(out, "{}", base),
+            Color::Red => write!(out, "{}", base + 1),
+            Color::Green => write!(out, "{}", base + 2),
+            Color::Yellow => write!(out, "{}", base + 3),
+            Color::Blue => write!(out, "{}", base + 4),
+            Color::Magenta => write!(out, "{}", base + 5),
+            Color::Cyan => write!(out, "{}", base + 6),
+            Color::White => write!(out, "{}", base + 7),
+            Color::BrightBlack => write!(out, "{}", bright_base),
+            Color::BrightRed => write!(out, "{}", bright_base + 1),
+            Color::BrightGreen => write!(out, "{}", bright_base + 2),
+            Color::BrightYellow => write!(out, "{}", bright_base + 3),
+            Color::BrightBlue => write!(out, "{}", bright_base + 4),
+            Color::BrightMagenta => write!(out, "{}", bright_base + 5),
+            Color::BrightCyan => write!(out, "{}", bright_base + 6),
+            Color::BrightWhite => write!(out, "{}", bright_base + 7),
+            Color::Fixed(n) => write!(out, "{};5;{}", extended, n),
+            Color::RGB(r, g, b) => write!(out, "{};2;{};{};{}", extended, r, g, b),
+            Color::Default => write!(out, "{}", base + 9),
+        };
+    }
+
+    /// Approximate this color as a 24-bit RGB triple. `RGB` colors are returned unchanged, and
+    /// `Fixed` colors use the standard xterm 256-color palette. The actual values of the named
+    /// colors (`Red`, `BrightRed`, ..) depend on the terminal, so they are approximated using the
+    /// default xterm palette. `Default` is approximated as `White`.
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        match self {
+            Color::Black => (0, 0, 0),
+            Color::Red => (205, 0, 0),
+            Color::Green => (0, 205, 0),
+            Color::Yellow => (205, 205, 0),
+            Color::Blue => (0, 0, 238),
+            Color::Magenta => (205, 0, 205),
+            Color::Cyan => (0, 205, 205),
+            Color::White | Color::Default => (229, 229, 229),
+            Color::BrightBlack => (127, 127, 127),
+            Color::BrightRed => (255, 0, 0),
+            Color::BrightGreen => (0, 255, 0),
+            Color::BrightYellow => (255, 255, 0),
+            Color::BrightBlue => (92, 92, 255),
+            Color::BrightMagenta => (255, 0, 255),
+            Color::BrightCyan => (0, 255, 255),
+            Color::BrightWhite => (255, 255, 255),
+            Color::Fixed(n @ 0..=15) => Color::from_ansi_index(*n).to_rgb(),
+            Color::Fixed(n @ 16..=231) => {
+                // 6x6x6 color cube
+                const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+                let n = n - 16;
+                (
+                    LEVELS[usize::from(n / 36)],
+                    LEVELS[usize::from(n / 6 % 6)],
+                    LEVELS[usize::from(n % 6)],
+                )
+            }
+            Color::Fixed(n) => {
+                // Grayscale ramp
+                let level = 8 + 10 * (n - 232);
+                (level, level, level)
+            }
+            Color::RGB(r, g, b) => (*r, *g, *b),
+        }
+    }
+
+    /// Convert to a 24-bit `RGB` color using [`to_rgb`](#method.to_rgb), so that it looks the same
+    /// regardless of the terminal's palette. `Default` is returned unchanged.
+    pub fn to_rgb_color(&self) -> Color {
+        match self {
+            Color::Default => Color::Default,
+            _ => {
+                let (r, g, b) = self.to_rgb();
+                Color::RGB(r, g, b)
+            }
+        }
+    }
+
+    /// Like [`to_rgb_color`](#method.to_rgb_color), but with a custom palette for the 16 named
+    /// colors (and `Fixed(0)`-`Fixed(15)`), in the order `Black`, `Red`, .., `BrightWhite`.
+    pub fn to_rgb_color_with_palette(&self, palette: &[(u8, u8, u8); 16]) -> Color {
+        match self.to_ansi_index() {
+            Some(n @ 0..=15) => {
+                let (r, g, b) = palette[usize::from(n)];
+                Color::RGB(r, g, b)
+            }
+            _ => self.to_rgb_color(),
+        }
+    }
+
+    /// Parse a hexadecimal color like `#ff0080` or `ff0080` into an `RGB` color.
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Color::RGB(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Format this color as a hexadecimal string like `#ff0080`. `Fixed` colors are converted
+    /// using the xterm palette (see [`to_rgb`](#method.to_rgb)). Returns `None` for the named
+    /// colors, since their actual values depend on the terminal.
+    pub fn to_hex(&self) -> Option<String> {
+        match self {
+            Color::Fixed(_) | Color::RGB(..) => {
+                let (r, g, b) = self.to_rgb();
+                Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert to the closest 8-bit color (`Fixed`), using the xterm color cube and grayscale
+    /// ramp. The named colors are mapped to their respective indices `0`-`15`, and `Default` is
+    /// returned unchanged.
+    pub fn to_fixed(&self) -> Color {
+        match self {
+            Color::Fixed(_) | Color::Default => self.clone(),
+            Color::RGB(r, g, b) => {
+                let rgb = (*r, *g, *b);
+
+                // Closest color in the 6x6x6 color cube
+                let cube_index = |v: u8| match v {
+                    0..=47 => 0,
+                    48..=114 => 1,
+                    _ => (v - 35) / 40,
+                };
+                let (ri, gi, bi) = (cube_index(*r), cube_index(*g), cube_index(*b));
+                let cube = Color::Fixed(16 + 36 * ri + 6 * gi + bi);
+
+                // Closest color in the grayscale ramp
+                let average = (u16::from(*r) + u16::from(*g) + u16::from(*b)) / 3;
+                let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+                let gray = Color::Fixed(232 + gray_index);
+
+                if distance(rgb, gray.to_rgb()) < distance(rgb, cube.to_rgb()) {
+                    gray
+                } else {
+                    cube
+                }
+            }
+            named => Color::Fixed(named.to_ansi_index().unwrap_or(0)),
+        }
+    }
+
+    /// Convert to the closest of the 16 named ANSI colors, based on the approximate RGB values
+    /// from [`to_rgb`](#method.to_rgb). `Default` is returned unchanged.
+    pub fn to_named(&self) -> Color {
+        match self {
+            Color::Fixed(n @ 0..=15) => Color::from_ansi_index(*n),
+            Color::Fixed(_) | Color::RGB(..) => {
+                let rgb = self.to_rgb();
+                (0..16)
+                    .map(Color::from_ansi_index)
+                    .min_by_key(|named| distance(rgb, named.to_rgb()))
+                    .unwrap_or(Color::Black)
+            }
+            named => named.clone(),
+        }
+    }
+
+    /// Get the palette index of a named or `Fixed` color.
+    fn to_ansi_index(&self) -> Option<u8> {
+        match self {
+            Color::Fixed(n) => Some(*n),
+            Color::RGB(..) | Color::Default => None,
+            named => (0..16).find(|&n| Color::from_ansi_index(n) == *named),
+        }
+    }
+
+    /// Get the named color for one of the 16 standard ANSI color indices.
+    fn from_ansi_index(n: u8) -> Color {
+        match n {
+            0 => Color::Black,
+            1 => Color::Red,
+            2 => Color::Green,
+            3 => Color::Yellow,
+            4 => Color::Blue,
+            5 => Color::Magenta,
+            6 => Color::Cyan,
+            7 => Color::White,
+            8 => Color::BrightBlack,
+            9 => Color::BrightRed,
+            10 => Color::BrightGreen,
+            11 => Color::BrightYellow,
+            12 => Color::BrightBlue,
+            13 => Color::BrightMagenta,
+            14 => Color::BrightCyan,
+            _ => Color::BrightWhite,
+        }
+    }
+
+    /// Convert to a `ansi_term::Color` (if the `ansi_term` feature is enabled). `ansi_term` has no
+    /// default color, so `Default` is approximated as `White`.
     #[cfg(feature = "ansi_term")]
     pub fn to_ansi_term_color(&self) -> ansi_term::Color {
         match self {
@@ -48,7 +283,7 @@ impl Color {
             Color::Blue => ansi_term::Color::Blue,
             Color::Magenta => ansi_term::Color::Purple,
             Color::Cyan => ansi_term::Color::Cyan,
-            Color::White => ansi_term::Color::White,
+            Color::White | Color::Default => ansi_term::Color::White,
 
             // Below items are a rough translations to 256 colors as
             // we do not have bright varients available on ansi-term
@@ -63,6 +298,60 @@ impl Color {
         }
     }
 
+    /// Convert to a `nu_ansi_term::Color` (if the `nu-ansi-term` feature is enabled).
+    #[cfg(feature = "nu-ansi-term")]
+    pub fn to_nu_ansi_term_color(&self) -> nu_ansi_term::Color {
+        match self {
+            Color::RGB(r, g, b) => nu_ansi_term::Color::Rgb(*r, *g, *b),
+            Color::Fixed(n) => nu_ansi_term::Color::Fixed(*n),
+            Color::Black => nu_ansi_term::Color::Black,
+            Color::Red => nu_ansi_term::Color::Red,
+            Color::Green => nu_ansi_term::Color::Green,
+            Color::Yellow => nu_ansi_term::Color::Yellow,
+            Color::Blue => nu_ansi_term::Color::Blue,
+            Color::Magenta => nu_ansi_term::Color::Magenta,
+            Color::Cyan => nu_ansi_term::Color::Cyan,
+            Color::White => nu_ansi_term::Color::White,
+            Color::BrightBlack => nu_ansi_term::Color::DarkGray,
+            Color::BrightRed => nu_ansi_term::Color::LightRed,
+            Color::BrightGreen => nu_ansi_term::Color::LightGreen,
+            Color::BrightYellow => nu_ansi_term::Color::LightYellow,
+            Color::BrightBlue => nu_ansi_term::Color::LightBlue,
+            Color::BrightMagenta => nu_ansi_term::Color::LightMagenta,
+            Color::BrightCyan => nu_ansi_term::Color::LightCyan,
+            Color::BrightWhite => nu_ansi_term::Color::LightGray,
+            Color::Default => nu_ansi_term::Color::Default,
+        }
+    }
+
+    /// Convert to a `termcolor::Color` (if the `termcolor` feature is enabled). The bright colors
+    /// are mapped to `Ansi256(8)`-`Ansi256(15)`, since the `intense` flag of `termcolor` applies to
+    /// both the foreground and the background. `termcolor` has no default color, so `Default` is
+    /// approximated as `White`.
+    #[cfg(feature = "termcolor")]
+    pub fn to_termcolor_color(&self) -> termcolor::Color {
+        match self {
+            Color::RGB(r, g, b) => termcolor::Color::Rgb(*r, *g, *b),
+            Color::Fixed(n) => termcolor::Color::Ansi256(*n),
+            Color::Black => termcolor::Color::Black,
+            Color::Red => termcolor::Color::Red,
+            Color::Green => termcolor::Color::Green,
+            Color::Yellow => termcolor::Color::Yellow,
+            Color::Blue => termcolor::Color::Blue,
+            Color::Magenta => termcolor::Color::Magenta,
+            Color::Cyan => termcolor::Color::Cyan,
+            Color::White | Color::Default => termcolor::Color::White,
+            Color::BrightBlack => termcolor::Color::Ansi256(8),
+            Color::BrightRed => termcolor::Color::Ansi256(9),
+            Color::BrightGreen => termcolor::Color::Ansi256(10),
+            Color::BrightYellow => termcolor::Color::Ansi256(11),
+            Color::BrightBlue => termcolor::Color::Ansi256(12),
+            Color::BrightMagenta => termcolor::Color::Ansi256(13),
+            Color::BrightCyan => termcolor::Color::Ansi256(14),
+            Color::BrightWhite => termcolor::Color::Ansi256(15),
+        }
+    }
+
     /// Convert to a `crossterm::style::Color` (if the `crossterm` feature is enabled).
     #[cfg(feature = "crossterm")]
     pub fn to_crossterm_color(&self) -> crossterm::style::Color {
@@ -89,22 +378,246 @@ impl Color {
             Color::BrightMagenta => crossterm::style::Color::Magenta,
             Color::BrightCyan => crossterm::style::Color::Cyan,
             Color::BrightWhite => crossterm::style::Color::White,
+            Color::Default => crossterm::style::Color::Reset,
+        }
+    }
+
+    /// Convert to a `anstyle::Color` (if the `anstyle` feature is enabled). `anstyle` has no
+    /// default color, so `Default` is approximated as `White`.
+    #[cfg(feature = "anstyle")]
+    pub fn to_anstyle_color(&self) -> anstyle::Color {
+        match self {
+            Color::RGB(r, g, b) => anstyle::Color::Rgb(anstyle::RgbColor(*r, *g, *b)),
+            Color::Fixed(n) => anstyle::Color::Ansi256(anstyle::Ansi256Color(*n)),
+            Color::Black => anstyle::Color::Ansi(anstyle::AnsiColor::Black),
+            Color::Red => anstyle::Color::Ansi(anstyle::AnsiColor::Red),
+            Color::Green => anstyle::Color::Ansi(anstyle::AnsiColor::Green),
+            Color::Yellow => anstyle::Color::Ansi(anstyle::AnsiColor::Yellow),
+            Color::Blue => anstyle::Color::Ansi(anstyle::AnsiColor::Blue),
+            Color::Magenta => anstyle::Color::Ansi(anstyle::AnsiColor::Magenta),
+            Color::Cyan => anstyle::Color::Ansi(anstyle::AnsiColor::Cyan),
+            Color::White | Color::Default => anstyle::Color::Ansi(anstyle::AnsiColor::White),
+            Color::BrightBlack => anstyle::Color::Ansi(anstyle::AnsiColor::BrightBlack),
+            Color::BrightRed => anstyle::Color::Ansi(anstyle::AnsiColor::BrightRed),
+            Color::BrightGreen => anstyle::Color::Ansi(anstyle::AnsiColor::BrightGreen),
+            Color::BrightYellow => anstyle::Color::Ansi(anstyle::AnsiColor::BrightYellow),
+            Color::BrightBlue => anstyle::Color::Ansi(anstyle::AnsiColor::BrightBlue),
+            Color::BrightMagenta => anstyle::Color::Ansi(anstyle::AnsiColor::BrightMagenta),
+            Color::BrightCyan => anstyle::Color::Ansi(anstyle::AnsiColor::BrightCyan),
+            Color::BrightWhite => anstyle::Color::Ansi(anstyle::AnsiColor::BrightWhite),
+        }
+    }
+
+    /// Convert to a `owo_colors::DynColors` (if the `owo-colors` feature is enabled).
+    #[cfg(feature = "owo-colors")]
+    pub fn to_owo_color(&self) -> owo_colors::DynColors {
+        use owo_colors::{AnsiColors, DynColors, XtermColors};
+
+        match self {
+            Color::RGB(r, g, b) => DynColors::Rgb(*r, *g, *b),
+            Color::Fixed(n) => DynColors::Xterm(XtermColors::from(*n)),
+            Color::Black => DynColors::Ansi(AnsiColors::Black),
+            Color::Red => DynColors::Ansi(AnsiColors::Red),
+            Color::Green => DynColors::Ansi(AnsiColors::Green),
+            Color::Yellow => DynColors::Ansi(AnsiColors::Yellow),
+            Color::Blue => DynColors::Ansi(AnsiColors::Blue),
+            Color::Magenta => DynColors::Ansi(AnsiColors::Magenta),
+            Color::Cyan => DynColors::Ansi(AnsiColors::Cyan),
+            Color::White => DynColors::Ansi(AnsiColors::White),
+            Color::BrightBlack => DynColors::Ansi(AnsiColors::BrightBlack),
+            Color::BrightRed => DynColors::Ansi(AnsiColors::BrightRed),
+            Color::BrightGreen => DynColors::Ansi(AnsiColors::BrightGreen),
+            Color::BrightYellow => DynColors::Ansi(AnsiColors::BrightYellow),
+            Color::BrightBlue => DynColors::Ansi(AnsiColors::BrightBlue),
+            Color::BrightMagenta => DynColors::Ansi(AnsiColors::BrightMagenta),
+            Color::BrightCyan => DynColors::Ansi(AnsiColors::BrightCyan),
+            Color::BrightWhite => DynColors::Ansi(AnsiColors::BrightWhite),
+            Color::Default => DynColors::Ansi(AnsiColors::Default),
         }
     }
 }
 
+impl FromStr for Color {
+    type Err = ParseStyleError;
+
+    /// Parse a single foreground color code, like `31`, `91`, `38;5;115` or `38;2;255;0;100`.
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let mut parts = code
+            .split(';')
+            .map(parse_sgr_parameter)
+            .collect::<Option<VecDeque<_>>>()
+            .ok_or(ParseStyleError)?;
+
+        let color = match parts.pop_front().as_deref() {
+            Some(&[n @ 30..=37]) => Some(Color::from_ansi_index((n - 30) as u8)),
+            Some(&[n @ 90..=97]) => Some(Color::from_ansi_index((n - 90 + 8) as u8)),
+            Some(&[38, ref subparams @ ..]) => {
+                parse_extended_color(subparams, &mut parts).flatten()
+            }
+            Some(&[39]) => Some(Color::Default),
+            _ => None,
+        };
+
+        match color {
+            Some(color) if parts.is_empty() => Ok(color),
+            _ => Err(ParseStyleError),
+        }
+    }
+}
+
+#[cfg(feature = "anstyle")]
+impl From<anstyle::Color> for Color {
+    fn from(color: anstyle::Color) -> Self {
+        match color {
+            anstyle::Color::Rgb(anstyle::RgbColor(r, g, b)) => Color::RGB(r, g, b),
+            anstyle::Color::Ansi256(anstyle::Ansi256Color(n)) => Color::Fixed(n),
+            anstyle::Color::Ansi(color) => match color {
+                anstyle::AnsiColor::Black => Color::Black,
+                anstyle::AnsiColor::Red => Color::Red,
+                anstyle::AnsiColor::Green => Color::Green,
+                anstyle::AnsiColor::Yellow => Color::Yellow,
+                anstyle::AnsiColor::Blue => Color::Blue,
+                anstyle::AnsiColor::Magenta => Color::Magenta,
+                anstyle::AnsiColor::Cyan => Color::Cyan,
+                anstyle::AnsiColor::White => Color::White,
+                anstyle::AnsiColor::BrightBlack => Color::BrightBlack,
+                anstyle::AnsiColor::BrightRed => Color::BrightRed,
+                anstyle::AnsiColor::BrightGreen => Color::BrightGreen,
+                anstyle::AnsiColor::BrightYellow => Color::BrightYellow,
+                anstyle::AnsiColor::BrightBlue => Color::BrightBlue,
+                anstyle::AnsiColor::BrightMagenta => Color::BrightMagenta,
+                anstyle::AnsiColor::BrightCyan => Color::BrightCyan,
+                anstyle::AnsiColor::BrightWhite => Color::BrightWhite,
+            },
+        }
+    }
+}
+
+/// Count the visible characters of a string, ignoring ANSI escape sequences like `\x1b[1;34m`
+/// (CSI sequences). This is useful for aligning columns of painted file names. Note that this
+/// counts `char`s, so wide characters like CJK ideographs are only counted once.
+///
+/// ```
+/// use lscolors::style::visible_width;
+///
+/// assert_eq!(4, visible_width("\x1b[1;34mdir/\x1b[0m"));
+/// ```
+pub fn visible_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.clone().next() == Some('[') {
+            chars.next();
+            // Skip the parameter and intermediate bytes, up to and including the final byte
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+
+    width
+}
+
+/// Parse a single `;`-separated SGR parameter into its `:`-separated subparameters
+/// (ISO 8613-6), e.g. `38:2::255:0:0` becomes `[38, 2, 0, 255, 0, 0]`. Empty subparameters
+/// (like the color space ID above) default to `0`. Values are not limited to `u8`, so that an
+/// out-of-range color component only invalidates that color.
+fn parse_sgr_parameter(param: &str) -> Option<Vec<u32>> {
+    let mut subparams = param.split(':');
+    let mut parsed = vec![subparams.next()?.parse().ok()?];
+    for subparam in subparams {
+        if subparam.is_empty() {
+            parsed.push(0);
+        } else {
+            parsed.push(subparam.parse().ok()?);
+        }
+    }
+    Some(parsed)
+}
+
+/// Parse the color following an extended color code (`38`, `48` or `58`), either from its
+/// subparameters (`38:5:n`) or from the following parameters (`38;5;n`).
+///
+/// Returns `None` if the sequence is malformed, and `Some(None)` if it is well-formed but a color
+/// component is out of range (like `38;5;300`), in which case the color should be skipped.
+fn parse_extended_color(
+    subparams: &[u32],
+    parts: &mut VecDeque<Vec<u32>>,
+) -> Option<Option<Color>> {
+    let fixed = |n: u32| Some(Color::Fixed(u8::try_from(n).ok()?));
+    let rgb = |r: u32, g: u32, b: u32| {
+        Some(Color::RGB(
+            u8::try_from(r).ok()?,
+            u8::try_from(g).ok()?,
+            u8::try_from(b).ok()?,
+        ))
+    };
+
+    match *subparams {
+        [5, n] => Some(fixed(n)),
+        // With or without a color space ID
+        [2, red, green, blue] | [2, _, red, green, blue] => Some(rgb(red, green, blue)),
+        [] => {
+            let mut next = || match parts.pop_front()?.as_slice() {
+                [n] => Some(*n),
+                _ => None,
+            };
+            match next()? {
+                5 => Some(fixed(next()?)),
+                2 => Some(rgb(next()?, next()?, next()?)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Skip `Color::Default`, for libraries where the default color is the absence of a color.
+#[cfg(any(feature = "ansi_term", feature = "anstyle", feature = "termcolor"))]
+fn non_default(color: &Option<Color>) -> Option<&Color> {
+    color.as_ref().filter(|color| **color != Color::Default)
+}
+
+/// Squared euclidean distance between two RGB colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// The style of an underline. Besides the classic single underline (SGR `4`), many terminals
+/// support styled underlines with the `4:n` subparameter form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnderlineStyle {
+    #[default]
+    None,
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
 /// Font-style attributes.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct FontStyle {
     pub bold: bool,
     pub dimmed: bool, // a.k.a. faint
     pub italic: bool,
-    pub underline: bool,
+    pub underline: UnderlineStyle,
     pub slow_blink: bool,
     pub rapid_blink: bool,
     pub reverse: bool,       // a.k.a. inverse or reverse video
     pub hidden: bool,        // a.k.a. conceal
     pub strikethrough: bool, // a.k.a. crossed-out
+    pub overline: bool,
 }
 
 impl FontStyle {
@@ -131,7 +644,7 @@ impl FontStyle {
 
     pub fn underline() -> Self {
         FontStyle {
-            underline: true,
+            underline: UnderlineStyle::Single,
             ..Default::default()
         }
     }
@@ -171,7 +684,147 @@ impl FontStyle {
         }
     }
 
-    /// Convert to `crossterm::style::Attributes` (if the `crossterm` feature is enabled).
+    pub fn overline() -> Self {
+        FontStyle {
+            overline: true,
+            ..Default::default()
+        }
+    }
+
+    /// Enable or disable bold. Together with the other `with_*()` methods, this allows chaining,
+    /// e.g. `FontStyle::default().with_bold(true).with_italic(true)`.
+    pub fn with_bold(mut self, bold: bool) -> Self {
+        self.bold = bold;
+        self
+    }
+
+    /// Enable or disable dimmed (faint).
+    pub fn with_dimmed(mut self, dimmed: bool) -> Self {
+        self.dimmed = dimmed;
+        self
+    }
+
+    /// Enable or disable italic.
+    pub fn with_italic(mut self, italic: bool) -> Self {
+        self.italic = italic;
+        self
+    }
+
+    /// Set the underline style, e.g. `FontStyle::bold().with_underline(UnderlineStyle::Curly)`.
+    pub fn with_underline(mut self, underline: UnderlineStyle) -> Self {
+        self.underline = underline;
+        self
+    }
+
+    /// Enable or disable slow blink.
+    pub fn with_slow_blink(mut self, slow_blink: bool) -> Self {
+        self.slow_blink = slow_blink;
+        self
+    }
+
+    /// Enable or disable rapid blink.
+    pub fn with_rapid_blink(mut self, rapid_blink: bool) -> Self {
+        self.rapid_blink = rapid_blink;
+        self
+    }
+
+    /// Enable or disable reverse video.
+    pub fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Enable or disable hidden (concealed).
+    pub fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Enable or disable strikethrough (crossed-out).
+    pub fn with_strikethrough(mut self, strikethrough: bool) -> Self {
+        self.strikethrough = strikethrough;
+        self
+    }
+
+    /// Enable or disable overline.
+    pub fn with_overline(mut self, overline: bool) -> Self {
+        self.overline = overline;
+        self
+    }
+
+    /// Combine two font styles, enabling every attribute that is enabled in either of them. If
+    /// both are underlined, the underline style of `other` wins.
+    pub fn union(self, other: FontStyle) -> Self {
+        FontStyle {
+            bold: self.bold || other.bold,
+            dimmed: self.dimmed || other.dimmed,
+            italic: self.italic || other.italic,
+            underline: if other.is_underlined() {
+                other.underline
+            } else {
+                self.underline
+            },
+            slow_blink: self.slow_blink || other.slow_blink,
+            rapid_blink: self.rapid_blink || other.rapid_blink,
+            reverse: self.reverse || other.reverse,
+            hidden: self.hidden || other.hidden,
+            strikethrough: self.strikethrough || other.strikethrough,
+            overline: self.overline || other.overline,
+        }
+    }
+
+    /// Whether bold is enabled.
+    pub fn is_bold(&self) -> bool {
+        self.bold
+    }
+
+    /// Whether dimmed (faint) is enabled.
+    pub fn is_dimmed(&self) -> bool {
+        self.dimmed
+    }
+
+    /// Whether italic is enabled.
+    pub fn is_italic(&self) -> bool {
+        self.italic
+    }
+
+    /// Whether any kind of underline is enabled.
+    pub fn is_underlined(&self) -> bool {
+        self.underline != UnderlineStyle::None
+    }
+
+    /// Whether slow blink is enabled.
+    pub fn is_slow_blink(&self) -> bool {
+        self.slow_blink
+    }
+
+    /// Whether rapid blink is enabled.
+    pub fn is_rapid_blink(&self) -> bool {
+        self.rapid_blink
+    }
+
+    /// Whether reverse video is enabled.
+    pub fn is_reverse(&self) -> bool {
+        self.reverse
+    }
+
+    /// Whether hidden (concealed) is enabled.
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Whether strikethrough (crossed-out) is enabled.
+    pub fn is_strikethrough(&self) -> bool {
+        self.strikethrough
+    }
+
+    /// Whether overline is enabled.
+    pub fn is_overline(&self) -> bool {
+        self.overline
+    }
+
+    /// Convert to `crossterm::style::Attributes` (if the `crossterm` feature is enabled). Styled
+    /// underlines are converted to a single underline.
     #[cfg(feature = "crossterm")]
     pub fn to_crossterm_attributes(&self) -> crossterm::style::Attributes {
         let mut attributes = crossterm::style::Attributes::default();
@@ -184,7 +837,7 @@ impl FontStyle {
         if self.italic {
             attributes.set(crossterm::style::Attribute::Italic);
         }
-        if self.underline {
+        if self.is_underlined() {
             attributes.set(crossterm::style::Attribute::Underlined);
         }
         if self.slow_blink {
@@ -202,16 +855,87 @@ impl FontStyle {
         if self.strikethrough {
             attributes.set(crossterm::style::Attribute::CrossedOut);
         }
+        if self.overline {
+            attributes.set(crossterm::style::Attribute::OverLined);
+        }
         attributes
     }
+
+    /// Convert to `anstyle::Effects` (if the `anstyle` feature is enabled). Overline is dropped,
+    /// since `anstyle` cannot represent it.
+    #[cfg(feature = "anstyle")]
+    pub fn to_anstyle_effects(&self) -> anstyle::Effects {
+        anstyle::Effects::new()
+            .set(anstyle::Effects::BOLD, self.bold)
+            .set(anstyle::Effects::DIMMED, self.dimmed)
+            .set(anstyle::Effects::ITALIC, self.italic)
+            .set(
+                anstyle::Effects::UNDERLINE,
+                self.underline == UnderlineStyle::Single,
+            )
+            .set(
+                anstyle::Effects::DOUBLE_UNDERLINE,
+                self.underline == UnderlineStyle::Double,
+            )
+            .set(
+                anstyle::Effects::CURLY_UNDERLINE,
+                self.underline == UnderlineStyle::Curly,
+            )
+            .set(
+                anstyle::Effects::DOTTED_UNDERLINE,
+                self.underline == UnderlineStyle::Dotted,
+            )
+            .set(
+                anstyle::Effects::DASHED_UNDERLINE,
+                self.underline == UnderlineStyle::Dashed,
+            )
+            .set(anstyle::Effects::BLINK, self.slow_blink || self.rapid_blink)
+            .set(anstyle::Effects::INVERT, self.reverse)
+            .set(anstyle::Effects::HIDDEN, self.hidden)
+            .set(anstyle::Effects::STRIKETHROUGH, self.strikethrough)
+    }
+}
+
+#[cfg(feature = "anstyle")]
+impl From<anstyle::Effects> for FontStyle {
+    fn from(effects: anstyle::Effects) -> Self {
+        FontStyle {
+            bold: effects.contains(anstyle::Effects::BOLD),
+            dimmed: effects.contains(anstyle::Effects::DIMMED),
+            italic: effects.contains(anstyle::Effects::ITALIC),
+            underline: if effects.contains(anstyle::Effects::UNDERLINE) {
+                UnderlineStyle::Single
+            } else if effects.contains(anstyle::Effects::DOUBLE_UNDERLINE) {
+                UnderlineStyle::Double
+            } else if effects.contains(anstyle::Effects::CURLY_UNDERLINE) {
+                UnderlineStyle::Curly
+            } else if effects.contains(anstyle::Effects::DOTTED_UNDERLINE) {
+                UnderlineStyle::Dotted
+            } else if effects.contains(anstyle::Effects::DASHED_UNDERLINE) {
+                UnderlineStyle::Dashed
+            } else {
+                UnderlineStyle::None
+            },
+            slow_blink: effects.contains(anstyle::Effects::BLINK),
+            rapid_blink: false,
+            reverse: effects.contains(anstyle::Effects::INVERT),
+            hidden: effects.contains(anstyle::Effects::HIDDEN),
+            strikethrough: effects.contains(anstyle::Effects::STRIKETHROUGH),
+            overline: false,
+        }
+    }
 }
 
-/// A foreground color, background color and font-style.
-#[derive(Debug, Clone, PartialEq, Default)]
+/// A foreground color, background color, font-style and underline color.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Style {
     pub foreground: Option<Color>,
     pub background: Option<Color>,
     pub font_style: FontStyle,
+    /// The underline color (SGR `58`, or `59` for `Color::Default`), as supported by kitty, iTerm2
+    /// and others.
+    pub underline_color: Option<Color>,
 }
 
 impl Style {
@@ -221,118 +945,119 @@ impl Style {
             return None;
         }
 
-        let mut parts: VecDeque<u8> = code
+        let mut parts = code
             .split(';')
-            .map(|c| c.parse::<u8>().ok())
-            .collect::<Option<_>>()?;
+            .map(parse_sgr_parameter)
+            .collect::<Option<VecDeque<_>>>()?;
 
         let mut font_style = FontStyle::default();
         let mut foreground = None;
         let mut background = None;
+        let mut underline_color = None;
 
-        loop {
-            match parts.pop_front() {
-                Some(0) => font_style = FontStyle::default(),
-                Some(1) => font_style.bold = true,
-                Some(2) => font_style.dimmed = true,
-                Some(3) => font_style.italic = true,
-                Some(4) => font_style.underline = true,
-                Some(5) => font_style.slow_blink = true,
-                Some(6) => font_style.rapid_blink = true,
-                Some(7) => font_style.reverse = true,
-                Some(8) => font_style.hidden = true,
-                Some(9) => font_style.strikethrough = true,
-                Some(22) => {
+        while let Some(part) = parts.pop_front() {
+            match part.as_slice() {
+                [0] => font_style = FontStyle::default(),
+                [1] => font_style.bold = true,
+                [2] => font_style.dimmed = true,
+                [3] => font_style.italic = true,
+                [4] | [4, 1] => font_style.underline = UnderlineStyle::Single,
+                [4, 0] => font_style.underline = UnderlineStyle::None,
+                [4, 2] | [21] => font_style.underline = UnderlineStyle::Double,
+                [4, 3] => font_style.underline = UnderlineStyle::Curly,
+                [4, 4] => font_style.underline = UnderlineStyle::Dotted,
+                [4, 5] => font_style.underline = UnderlineStyle::Dashed,
+                [5] => font_style.slow_blink = true,
+                [6] => font_style.rapid_blink = true,
+                [7] => font_style.reverse = true,
+                [8] => font_style.hidden = true,
+                [9] => font_style.strikethrough = true,
+                [22] => {
                     font_style.bold = false;
                     font_style.dimmed = false;
                 }
-                Some(23) => {
+                [23] => {
                     font_style.italic = false;
                 }
-                Some(24) => {
-                    font_style.underline = false;
+                [24] => {
+                    font_style.underline = UnderlineStyle::None;
                 }
-                Some(25) => {
+                [25] => {
                     font_style.slow_blink = false;
                     font_style.rapid_blink = false;
                 }
-                Some(27) => {
+                [27] => {
                     font_style.reverse = false;
                 }
-                Some(28) => {
+                [28] => {
                     font_style.hidden = false;
                 }
-                Some(29) => {
+                [29] => {
                     font_style.strikethrough = false;
                 }
-                Some(30) => foreground = Some(Color::Black),
-                Some(31) => foreground = Some(Color::Red),
-                Some(32) => foreground = Some(Color::Green),
-                Some(33) => foreground = Some(Color::Yellow),
-                Some(34) => foreground = Some(Color::Blue),
-                Some(35) => foreground = Some(Color::Magenta),
-                Some(36) => foreground = Some(Color::Cyan),
-                Some(37) => foreground = Some(Color::White),
-                Some(38) => match (parts.pop_front(), parts.pop_front()) {
-                    (Some(5), Some(color)) => foreground = Some(Color::Fixed(color)),
-                    (Some(2), Some(red)) => match (parts.pop_front(), parts.pop_front()) {
-                        (Some(green), Some(blue)) => {
-                            foreground = Some(Color::RGB(red, green, blue))
-                        }
-                        _ => {
-                            break;
-                        }
-                    },
-                    _ => {
+                [53] => font_style.overline = true,
+                [55] => {
+                    font_style.overline = false;
+                }
+                [30] => foreground = Some(Color::Black),
+                [31] => foreground = Some(Color::Red),
+                [32] => foreground = Some(Color::Green),
+                [33] => foreground = Some(Color::Yellow),
+                [34] => foreground = Some(Color::Blue),
+                [35] => foreground = Some(Color::Magenta),
+                [36] => foreground = Some(Color::Cyan),
+                [37] => foreground = Some(Color::White),
+                [38, subparams @ ..] => match parse_extended_color(subparams, &mut parts) {
+                    Some(Some(color)) => foreground = Some(color),
+                    Some(None) => {}
+                    None => {
+                        break;
+                    }
+                },
+                [39] => foreground = Some(Color::Default),
+                [40] => background = Some(Color::Black),
+                [41] => background = Some(Color::Red),
+                [42] => background = Some(Color::Green),
+                [43] => background = Some(Color::Yellow),
+                [44] => background = Some(Color::Blue),
+                [45] => background = Some(Color::Magenta),
+                [46] => background = Some(Color::Cyan),
+                [47] => background = Some(Color::White),
+                [48, subparams @ ..] => match parse_extended_color(subparams, &mut parts) {
+                    Some(Some(color)) => background = Some(color),
+                    Some(None) => {}
+                    None => {
                         break;
                     }
                 },
-                Some(39) => foreground = None,
-                Some(40) => background = Some(Color::Black),
-                Some(41) => background = Some(Color::Red),
-                Some(42) => background = Some(Color::Green),
-                Some(43) => background = Some(Color::Yellow),
-                Some(44) => background = Some(Color::Blue),
-                Some(45) => background = Some(Color::Magenta),
-                Some(46) => background = Some(Color::Cyan),
-                Some(47) => background = Some(Color::White),
-                Some(48) => match (parts.pop_front(), parts.pop_front()) {
-                    (Some(5), Some(color)) => background = Some(Color::Fixed(color)),
-                    (Some(2), Some(red)) => match (parts.pop_front(), parts.pop_front()) {
-                        (Some(green), Some(blue)) => {
-                            background = Some(Color::RGB(red, green, blue))
-                        }
-                        _ => {
-                            break;
-                        }
-                    },
-                    _ => {
+                [49] => background = Some(Color::Default),
+                [58, subparams @ ..] => match parse_extended_color(subparams, &mut parts) {
+                    Some(Some(color)) => underline_color = Some(color),
+                    Some(None) => {}
+                    None => {
                         break;
                     }
                 },
-                Some(49) => background = None,
-                Some(90) => foreground = Some(Color::BrightBlack),
-                Some(91) => foreground = Some(Color::BrightRed),
-                Some(92) => foreground = Some(Color::BrightGreen),
-                Some(93) => foreground = Some(Color::BrightYellow),
-                Some(94) => foreground = Some(Color::BrightBlue),
-                Some(95) => foreground = Some(Color::BrightMagenta),
-                Some(96) => foreground = Some(Color::BrightCyan),
-                Some(97) => foreground = Some(Color::BrightWhite),
-                Some(100) => background = Some(Color::BrightBlack),
-                Some(101) => background = Some(Color::BrightRed),
-                Some(102) => background = Some(Color::BrightGreen),
-                Some(103) => background = Some(Color::BrightYellow),
-                Some(104) => background = Some(Color::BrightBlue),
-                Some(105) => background = Some(Color::BrightMagenta),
-                Some(106) => background = Some(Color::BrightCyan),
-                Some(107) => background = Some(Color::BrightWhite),
-                Some(_) => {
+                [59] => underline_color = Some(Color::Default),
+                [90] => foreground = Some(Color::BrightBlack),
+                [91] => foreground = Some(Color::BrightRed),
+                [92] => foreground = Some(Color::BrightGreen),
+                [93] => foreground = Some(Color::BrightYellow),
+                [94] => foreground = Some(Color::BrightBlue),
+                [95] => foreground = Some(Color::BrightMagenta),
+                [96] => foreground = Some(Color::BrightCyan),
+                [97] => foreground = Some(Color::BrightWhite),
+                [100] => background = Some(Color::BrightBlack),
+                [101] => background = Some(Color::BrightRed),
+                [102] => background = Some(Color::BrightGreen),
+                [103] => background = Some(Color::BrightYellow),
+                [104] => background = Some(Color::BrightBlue),
+                [105] => background = Some(Color::BrightMagenta),
+                [106] => background = Some(Color::BrightCyan),
+                [107] => background = Some(Color::BrightWhite),
+                _ => {
                     continue;
                 }
-                None => {
-                    break;
-                }
             }
         }
 
@@ -340,19 +1065,113 @@ impl Style {
             foreground,
             background,
             font_style,
+            underline_color,
         })
     }
 
-    /// Convert to a `ansi_term::Style` (if the `ansi_term` feature is enabled).
+    /// Write the SGR parameters for this style to `out`, separated by `;`.
+    fn write_ansi_sequence(&self, out: &mut String) {
+        let start = out.len();
+
+        let font_style = &self.font_style;
+        let attributes = [
+            (font_style.bold, "1"),
+            (font_style.dimmed, "2"),
+            (font_style.italic, "3"),
+            (font_style.underline == UnderlineStyle::Single, "4"),
+            (font_style.underline == UnderlineStyle::Double, "4:2"),
+            (font_style.underline == UnderlineStyle::Curly, "4:3"),
+            (font_style.underline == UnderlineStyle::Dotted, "4:4"),
+            (font_style.underline == UnderlineStyle::Dashed, "4:5"),
+            (font_style.slow_blink, "5"),
+            (font_style.rapid_blink, "6"),
+            (font_style.reverse, "7"),
+            (font_style.hidden, "8"),
+            (font_style.strikethrough, "9"),
+            (font_style.overline, "53"),
+        ];
+        for (_, code) in attributes.iter().filter(|(enabled, _)| *enabled) {
+            if out.len() > start {
+                out.push(';');
+            }
+            out.push_str(code);
+        }
+
+        if let Some(foreground) = &self.foreground {
+            if out.len() > start {
+                out.push(';');
+            }
+            foreground.write_ansi_sequence(out, false);
+        }
+
+        if let Some(background) = &self.background {
+            if out.len() > start {
+                out.push(';');
+            }
+            background.write_ansi_sequence(out, true);
+        }
+
+        if let Some(underline_color) = &self.underline_color {
+            if out.len() > start {
+                out.push(';');
+            }
+            // There are no short codes for underline colors, so named colors use their index
+            let _ = match (underline_color, underline_color.to_ansi_index()) {
+                (Color::RGB(r, g, b), _) => write!(out, "58;2;{};{};{}", r, g, b),
+                (Color::Default, _) => write!(out, "59"),
+                (_, Some(n)) => write!(out, "58;5;{}", n),
+                (_, None) => Ok(()),
+            };
+        }
+
+        if out.len() == start {
+            out.push('0');
+        }
+    }
+
+    /// Whether this style has no colors and no font styles, i.e. whether it equals
+    /// `Style::default()`. Note that an explicit default color (`Color::Default`, from `39` or
+    /// `49`) still counts as a color.
+    pub fn is_default(&self) -> bool {
+        *self == Style::default()
+    }
+
+    /// Convert back to an ANSI sequence like `1;38;2;255;0;100` that can be parsed by
+    /// [`from_ansi_sequence`](#method.from_ansi_sequence). An unstyled `Style` becomes `0`.
+    pub fn to_ansi_sequence(&self) -> String {
+        let mut sequence = String::new();
+        self.write_ansi_sequence(&mut sequence);
+        sequence
+    }
+
+    /// Convert to an ANSI escape sequence like `"\x1b[1;34m"` that enables this style.
+    pub fn to_ansi_escape_sequence(&self) -> String {
+        let mut sequence = String::new();
+        self.write_ansi_escape_sequence(&mut sequence);
+        sequence
+    }
+
+    /// Like [`to_ansi_escape_sequence`](#method.to_ansi_escape_sequence), but appends to an
+    /// existing `String` instead of allocating a new one.
+    pub fn write_ansi_escape_sequence(&self, out: &mut String) {
+        out.push_str("\x1b[");
+        self.write_ansi_sequence(out);
+        out.push('m');
+    }
+
+    /// Convert to a `ansi_term::Style` (if the `ansi_term` feature is enabled). The underline
+    /// color and overline are ignored, since `ansi_term` cannot represent them. An explicit
+    /// default color (`39` or `49`) becomes no color at all, use
+    /// [`to_ansi_escape_sequence`](#method.to_ansi_escape_sequence) to keep it.
     #[cfg(feature = "ansi_term")]
     pub fn to_ansi_term_style(&self) -> ansi_term::Style {
         ansi_term::Style {
-            foreground: self.foreground.as_ref().map(Color::to_ansi_term_color),
-            background: self.background.as_ref().map(Color::to_ansi_term_color),
+            foreground: non_default(&self.foreground).map(Color::to_ansi_term_color),
+            background: non_default(&self.background).map(Color::to_ansi_term_color),
             is_bold: self.font_style.bold,
             is_dimmed: self.font_style.dimmed,
             is_italic: self.font_style.italic,
-            is_underline: self.font_style.underline,
+            is_underline: self.font_style.is_underlined(),
             is_blink: self.font_style.rapid_blink || self.font_style.slow_blink,
             is_reverse: self.font_style.reverse,
             is_hidden: self.font_style.hidden,
@@ -360,7 +1179,43 @@ impl Style {
         }
     }
 
-    /// Convert to a `crossterm::style::ContentStyle` (if the `crossterm` feature is enabled).
+    /// Convert to a `nu_ansi_term::Style` (if the `nu-ansi-term` feature is enabled). The underline
+    /// color and overline are ignored, since `nu_ansi_term` cannot represent them.
+    #[cfg(feature = "nu-ansi-term")]
+    pub fn to_nu_ansi_term_style(&self) -> nu_ansi_term::Style {
+        nu_ansi_term::Style {
+            foreground: self.foreground.as_ref().map(Color::to_nu_ansi_term_color),
+            background: self.background.as_ref().map(Color::to_nu_ansi_term_color),
+            is_bold: self.font_style.bold,
+            is_dimmed: self.font_style.dimmed,
+            is_italic: self.font_style.italic,
+            is_underline: self.font_style.is_underlined(),
+            is_blink: self.font_style.rapid_blink || self.font_style.slow_blink,
+            is_reverse: self.font_style.reverse,
+            is_hidden: self.font_style.hidden,
+            is_strikethrough: self.font_style.strikethrough,
+            ..nu_ansi_term::Style::default()
+        }
+    }
+
+    /// Convert to a `termcolor::ColorSpec` (if the `termcolor` feature is enabled). Blinking,
+    /// reverse video, hidden text, overline and the underline color are ignored, since `termcolor` cannot
+    /// represent them.
+    #[cfg(feature = "termcolor")]
+    pub fn to_termcolor_spec(&self) -> termcolor::ColorSpec {
+        let mut spec = termcolor::ColorSpec::new();
+        spec.set_fg(non_default(&self.foreground).map(Color::to_termcolor_color))
+            .set_bg(non_default(&self.background).map(Color::to_termcolor_color))
+            .set_bold(self.font_style.bold)
+            .set_dimmed(self.font_style.dimmed)
+            .set_italic(self.font_style.italic)
+            .set_underline(self.font_style.is_underlined())
+            .set_strikethrough(self.font_style.strikethrough);
+        spec
+    }
+
+    /// Convert to a `crossterm::style::ContentStyle` (if the `crossterm` feature is enabled). The
+    /// underline color is ignored, since `ContentStyle` cannot represent it.
     #[cfg(feature = "crossterm")]
     pub fn to_crossterm_style(&self) -> crossterm::style::ContentStyle {
         crossterm::style::ContentStyle {
@@ -369,11 +1224,90 @@ impl Style {
             attributes: self.font_style.to_crossterm_attributes(),
         }
     }
+
+    /// Convert to a `anstyle::Style` (if the `anstyle` feature is enabled).
+    #[cfg(feature = "anstyle")]
+    pub fn to_anstyle(&self) -> anstyle::Style {
+        anstyle::Style::new()
+            .fg_color(non_default(&self.foreground).map(Color::to_anstyle_color))
+            .bg_color(non_default(&self.background).map(Color::to_anstyle_color))
+            .effects(self.font_style.to_anstyle_effects())
+            .underline_color(non_default(&self.underline_color).map(Color::to_anstyle_color))
+    }
+
+    /// Convert to a `owo_colors::Style` (if the `owo-colors` feature is enabled). The underline
+    /// color and overline are ignored, and all underline styles become a single underline, since
+    /// `owo-colors` cannot represent them.
+    #[cfg(feature = "owo-colors")]
+    pub fn to_owo_style(&self) -> owo_colors::Style {
+        use owo_colors::Effect;
+
+        let mut style = owo_colors::Style::new();
+        if let Some(foreground) = &self.foreground {
+            style = style.color(foreground.to_owo_color());
+        }
+        if let Some(background) = &self.background {
+            style = style.on_color(background.to_owo_color());
+        }
+
+        let font_style = &self.font_style;
+        let effects = [
+            (font_style.bold, Effect::Bold),
+            (font_style.dimmed, Effect::Dimmed),
+            (font_style.italic, Effect::Italic),
+            (font_style.is_underlined(), Effect::Underline),
+            (font_style.slow_blink, Effect::Blink),
+            (font_style.rapid_blink, Effect::BlinkFast),
+            (font_style.reverse, Effect::Reversed),
+            (font_style.hidden, Effect::Hidden),
+            (font_style.strikethrough, Effect::Strikethrough),
+        ];
+        for (_, effect) in effects.iter().filter(|(enabled, _)| *enabled) {
+            style = style.effect(*effect);
+        }
+
+        style
+    }
+}
+
+impl FromStr for Style {
+    type Err = ParseStyleError;
+
+    /// Parse an ANSI sequence like `01;34`, see
+    /// [`from_ansi_sequence`](struct.Style.html#method.from_ansi_sequence).
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Style::from_ansi_sequence(code).ok_or(ParseStyleError)
+    }
+}
+
+/// The error returned when parsing a [`Style`] or [`Color`] via `str::parse` fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseStyleError;
+
+impl fmt::Display for ParseStyleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid ANSI style sequence")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseStyleError {}
+
+#[cfg(feature = "anstyle")]
+impl From<anstyle::Style> for Style {
+    fn from(style: anstyle::Style) -> Self {
+        Style {
+            foreground: style.get_fg_color().map(Color::from),
+            background: style.get_bg_color().map(Color::from),
+            font_style: style.get_effects().into(),
+            underline_color: style.get_underline_color().map(Color::from),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Color, FontStyle, Style};
+    use super::{Color, FontStyle, ParseStyleError, Style, UnderlineStyle};
 
     fn assert_style(
         code: &str,
@@ -422,10 +1356,211 @@ mod tests {
         assert_style("01;03", None, None, italic_and_bold);
     }
 
+    #[test]
+    fn parse_overline() {
+        assert_style("53;31", Some(Color::Red), None, FontStyle::overline());
+        assert_style("53;55", None, None, FontStyle::default());
+
+        let style = Style::from_ansi_sequence("53;31").unwrap();
+        assert_eq!(
+            Some(style.clone()),
+            Style::from_ansi_sequence(&style.to_ansi_sequence())
+        );
+    }
+
+    #[test]
+    fn parse_blink() {
+        assert_style("05;31", Some(Color::Red), None, FontStyle::slow_blink());
+        assert_style("31;05", Some(Color::Red), None, FontStyle::slow_blink());
+        assert_style("05;31;25", Some(Color::Red), None, FontStyle::default());
+    }
+
+    #[test]
+    fn parse_reverse() {
+        assert_style("07", None, None, FontStyle::reverse());
+        assert_style("34;07", Some(Color::Blue), None, FontStyle::reverse());
+        assert_style("34;07;0", Some(Color::Blue), None, FontStyle::default());
+        assert_style("34;07;27", Some(Color::Blue), None, FontStyle::default());
+    }
+
+    #[test]
+    fn parse_strikethrough() {
+        assert_style(
+            "9;31;44",
+            Some(Color::Red),
+            Some(Color::Blue),
+            FontStyle::strikethrough(),
+        );
+        assert_style("9;31;29", Some(Color::Red), None, FontStyle::default());
+    }
+
+    #[test]
+    fn parse_dimmed() {
+        assert_style("02;37", Some(Color::White), None, FontStyle::dimmed());
+        assert_style(
+            "01;02;37;22",
+            Some(Color::White),
+            None,
+            FontStyle::default(),
+        );
+    }
+
+    #[cfg(feature = "ansi_term")]
+    #[test]
+    fn to_ansi_term_style_dimmed() {
+        let style = Style::from_ansi_sequence("02;37").unwrap();
+        assert_eq!(ansi_term::Color::White.dimmed(), style.to_ansi_term_style());
+    }
+
+    #[cfg(feature = "nu-ansi-term")]
+    #[test]
+    fn to_nu_ansi_term_style() {
+        use nu_ansi_term::Color as NuColor;
+
+        let style = Style::from_ansi_sequence("01;34").unwrap();
+        let nu_style = style.to_nu_ansi_term_style();
+        assert_eq!(NuColor::Blue.bold(), nu_style);
+        assert_eq!("\x1b[1;34mfoo\x1b[0m", nu_style.paint("foo").to_string());
+
+        let style = Style::from_ansi_sequence("38;2;255;0;100;48;5;115;93").unwrap();
+        let nu_style = style.to_nu_ansi_term_style();
+        assert_eq!(NuColor::LightYellow.on(NuColor::Fixed(115)), nu_style);
+        assert_eq!(
+            "\x1b[48;5;115;93mfoo\x1b[0m",
+            nu_style.paint("foo").to_string()
+        );
+
+        let style = Style::from_ansi_sequence("38;2;255;0;100").unwrap();
+        let painted = style.to_nu_ansi_term_style().paint("foo").to_string();
+        assert_eq!(
+            Some(style),
+            Style::from_ansi_sequence(&painted[2..painted.find('m').unwrap()])
+        );
+    }
+
+    #[cfg(feature = "termcolor")]
+    #[test]
+    fn to_termcolor_spec() {
+        use termcolor::Color as TColor;
+
+        let spec = Style::from_ansi_sequence("01;34")
+            .unwrap()
+            .to_termcolor_spec();
+        assert_eq!(Some(&TColor::Blue), spec.fg());
+        assert_eq!(None, spec.bg());
+        assert!(spec.bold());
+        assert!(!spec.italic());
+
+        let spec = Style::from_ansi_sequence("03;04;38;2;1;2;3;48;5;115;39")
+            .unwrap()
+            .to_termcolor_spec();
+        assert_eq!(None, spec.fg());
+        assert_eq!(Some(&TColor::Ansi256(115)), spec.bg());
+        assert!(!spec.bold());
+        assert!(spec.italic());
+        assert!(spec.underline());
+
+        let spec = Style::from_ansi_sequence("91;9")
+            .unwrap()
+            .to_termcolor_spec();
+        assert_eq!(Some(&TColor::Ansi256(9)), spec.fg());
+        assert!(spec.strikethrough());
+    }
+
+    #[test]
+    fn parse_bright_foreground() {
+        assert_style("94", Some(Color::BrightBlue), None, FontStyle::default());
+        assert_style("01;91", Some(Color::BrightRed), None, FontStyle::bold());
+        assert_style("90;01", Some(Color::BrightBlack), None, FontStyle::bold());
+    }
+
+    #[test]
+    fn parse_bright_background() {
+        assert_style(
+            "30;101",
+            Some(Color::Black),
+            Some(Color::BrightRed),
+            FontStyle::default(),
+        );
+        assert_style(
+            "30;101;01",
+            Some(Color::Black),
+            Some(Color::BrightRed),
+            FontStyle::bold(),
+        );
+    }
+
     #[test]
     fn ignore_unsupported_styles() {
         let style = Style::from_ansi_sequence("14;31").unwrap();
         assert_eq!(Some(Color::Red), style.foreground);
+
+        assert_style(
+            "38;5;200;51;1",
+            Some(Color::Fixed(200)),
+            None,
+            FontStyle::bold(),
+        );
+    }
+
+    #[test]
+    fn stop_at_malformed_color() {
+        assert_style("01;38;7;31", None, None, FontStyle::bold());
+        assert_style("01;48;2;1;2", None, None, FontStyle::bold());
+    }
+
+    #[test]
+    fn to_rgb_color() {
+        assert_eq!(Color::RGB(0, 0, 255), Color::Fixed(21).to_rgb_color());
+        assert_eq!(Color::RGB(1, 2, 3), Color::RGB(1, 2, 3).to_rgb_color());
+        assert_eq!(Color::RGB(255, 0, 0), Color::BrightRed.to_rgb_color());
+        assert_eq!(Color::Default, Color::Default.to_rgb_color());
+
+        let mut palette = [(0, 0, 0); 16];
+        palette[4] = (10, 20, 30);
+        assert_eq!(
+            Color::RGB(10, 20, 30),
+            Color::Blue.to_rgb_color_with_palette(&palette)
+        );
+        assert_eq!(
+            Color::RGB(10, 20, 30),
+            Color::Fixed(4).to_rgb_color_with_palette(&palette)
+        );
+        assert_eq!(
+            Color::RGB(0, 0, 255),
+            Color::Fixed(21).to_rgb_color_with_palette(&palette)
+        );
+    }
+
+    #[test]
+    fn from_str() {
+        let style: Style = "01;34".parse().unwrap();
+        assert_eq!(Style::from_ansi_sequence("01;34"), Some(style));
+        assert_eq!(Err(ParseStyleError), "foo".parse::<Style>());
+        assert_eq!(Err(ParseStyleError), "".parse::<Style>());
+
+        assert_eq!(Ok(Color::Red), "31".parse());
+        assert_eq!(Ok(Color::BrightRed), "91".parse());
+        assert_eq!(Ok(Color::Fixed(115)), "38;5;115".parse());
+        assert_eq!(Ok(Color::RGB(255, 0, 100)), "38:2::255:0:100".parse());
+        assert_eq!(Ok(Color::Default), "39".parse());
+        assert_eq!(Err(ParseStyleError), "1".parse::<Color>());
+        assert_eq!(Err(ParseStyleError), "31;1".parse::<Color>());
+        assert_eq!(Err(ParseStyleError), "38;5;300".parse::<Color>());
+        assert_eq!(Err(ParseStyleError), "38;5".parse::<Color>());
+    }
+
+    #[test]
+    fn skip_out_of_range_color() {
+        assert_style("38;5;999;1", None, None, FontStyle::bold());
+        assert_style("31;38;5;256", Some(Color::Red), None, FontStyle::default());
+        assert_style("48;2;1;300;3;4", None, None, FontStyle::underline());
+        assert_style(
+            "38:5:1000;44",
+            None,
+            Some(Color::Blue),
+            FontStyle::default(),
+        );
     }
 
     #[test]
@@ -490,4 +1625,376 @@ mod tests {
             FontStyle::bold(),
         );
     }
+
+    #[test]
+    fn parse_default_colors() {
+        assert_style("39;1", Some(Color::Default), None, FontStyle::bold());
+        assert_style(
+            "31;49",
+            Some(Color::Red),
+            Some(Color::Default),
+            FontStyle::default(),
+        );
+
+        // An explicit default color is kept when converting back
+        let style = Style::from_ansi_sequence("39;1").unwrap();
+        assert_eq!("1;39", style.to_ansi_sequence());
+        assert_eq!(Some(style), Style::from_ansi_sequence("1;39"));
+        assert_ne!(
+            Style::from_ansi_sequence("1"),
+            Style::from_ansi_sequence("39;1")
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let style = Style::from_ansi_sequence("01;38;2;255;0;100;44").unwrap();
+
+        let json = serde_json::to_string(&style).unwrap();
+        assert!(json.starts_with(
+            r#"{"foreground":{"RGB":[255,0,100]},"background":"Blue","font_style":{"bold":true,"#
+        ));
+
+        let deserialized: Style = serde_json::from_str(&json).unwrap();
+        assert_eq!(style, deserialized);
+
+        let partial: Style = serde_json::from_str(
+            r#"{"foreground":{"Fixed":115},"background":null,"font_style":{"italic":true}}"#,
+        )
+        .unwrap();
+        assert_eq!(Some(Color::Fixed(115)), partial.foreground);
+        assert_eq!(FontStyle::italic(), partial.font_style);
+    }
+
+    #[test]
+    fn constructors_and_channels() {
+        assert_eq!(Color::RGB(255, 0, 100), Color::rgb(255, 0, 100));
+        assert_eq!(Color::Fixed(115), Color::fixed(115));
+
+        assert_eq!(Some((255, 0, 100)), Color::rgb(255, 0, 100).channels());
+        assert_eq!(None, Color::fixed(196).channels());
+        assert_eq!(None, Color::Red.channels());
+        assert_eq!(None, Color::Default.channels());
+    }
+
+    #[test]
+    fn to_rgb() {
+        assert_eq!((255, 0, 100), Color::RGB(255, 0, 100).to_rgb());
+        assert_eq!((255, 0, 0), Color::Fixed(196).to_rgb());
+        assert_eq!((0, 0, 0), Color::Fixed(16).to_rgb());
+        assert_eq!((255, 255, 255), Color::Fixed(231).to_rgb());
+        assert_eq!((135, 255, 175), Color::Fixed(121).to_rgb());
+        assert_eq!((8, 8, 8), Color::Fixed(232).to_rgb());
+        assert_eq!((238, 238, 238), Color::Fixed(255).to_rgb());
+        assert_eq!(Color::Red.to_rgb(), Color::Fixed(1).to_rgb());
+        assert_eq!(Color::BrightWhite.to_rgb(), Color::Fixed(15).to_rgb());
+    }
+
+    #[test]
+    fn hash_styles() {
+        use std::collections::HashSet;
+
+        let mut styles = HashSet::new();
+        styles.insert(Style::from_ansi_sequence("01;34").unwrap());
+        styles.insert(Style::from_ansi_sequence("34;01").unwrap());
+        assert_eq!(1, styles.len());
+
+        styles.insert(Style::from_ansi_sequence("38;2;0;0;255").unwrap());
+        styles.insert(Style::default());
+        assert_eq!(3, styles.len());
+    }
+
+    #[test]
+    fn font_style_combinators() {
+        let font_style = FontStyle::default()
+            .with_bold(true)
+            .with_underline(UnderlineStyle::Single);
+        assert_eq!(
+            Style::from_ansi_sequence("01;04").unwrap().font_style,
+            font_style
+        );
+        assert_eq!(font_style, FontStyle::bold().union(FontStyle::underline()));
+
+        let font_style = font_style.with_bold(false).with_italic(true);
+        assert!(!font_style.is_bold());
+        assert!(font_style.is_italic());
+        assert!(font_style.is_underlined());
+
+        let union = FontStyle::default()
+            .with_underline(UnderlineStyle::Curly)
+            .union(FontStyle::reverse());
+        assert_eq!(UnderlineStyle::Curly, union.underline);
+        assert!(union.is_reverse());
+    }
+
+    #[test]
+    fn font_style_getters() {
+        let font_style = Style::from_ansi_sequence("01;03;04;09;31")
+            .unwrap()
+            .font_style;
+        assert!(font_style.is_bold());
+        assert!(font_style.is_italic());
+        assert!(font_style.is_underlined());
+        assert!(font_style.is_strikethrough());
+        assert!(!font_style.is_dimmed());
+        assert!(!font_style.is_slow_blink());
+        assert!(!font_style.is_rapid_blink());
+        assert!(!font_style.is_reverse());
+        assert!(!font_style.is_hidden());
+    }
+
+    #[test]
+    fn parse_underline_style() {
+        assert_style("4", None, None, FontStyle::underline());
+        assert_style("04;31", Some(Color::Red), None, FontStyle::underline());
+
+        let style = Style::from_ansi_sequence("4:3").unwrap();
+        assert_eq!(UnderlineStyle::Curly, style.font_style.underline);
+        assert!(style.font_style.is_underlined());
+
+        let style = Style::from_ansi_sequence("21").unwrap();
+        assert_eq!(UnderlineStyle::Double, style.font_style.underline);
+
+        let style = Style::from_ansi_sequence("4:5;1").unwrap();
+        assert_eq!(UnderlineStyle::Dashed, style.font_style.underline);
+        assert!(style.font_style.bold);
+
+        let style = Style::from_ansi_sequence("4:3;4:0").unwrap();
+        assert_eq!(UnderlineStyle::None, style.font_style.underline);
+        assert!(!style.font_style.is_underlined());
+
+        let style = Style::from_ansi_sequence("4:4;24").unwrap();
+        assert!(!style.font_style.is_underlined());
+    }
+
+    #[test]
+    fn parse_underline_color() {
+        let style = Style::from_ansi_sequence("4;58;5;202").unwrap();
+        assert_eq!(FontStyle::underline(), style.font_style);
+        assert_eq!(Some(Color::Fixed(202)), style.underline_color);
+        assert_eq!(None, style.foreground);
+
+        let style = Style::from_ansi_sequence("58:2::255:0:100").unwrap();
+        assert_eq!(Some(Color::RGB(255, 0, 100)), style.underline_color);
+
+        let style = Style::from_ansi_sequence("58;5;202;59").unwrap();
+        assert_eq!(Some(Color::Default), style.underline_color);
+    }
+
+    #[test]
+    fn parse_colon_subparameters() {
+        assert_eq!(
+            Style::from_ansi_sequence("38;2;255;0;100"),
+            Style::from_ansi_sequence("38:2::255:0:100")
+        );
+        assert_style(
+            "38:2::255:0:100",
+            Some(Color::RGB(255, 0, 100)),
+            None,
+            FontStyle::default(),
+        );
+        assert_style(
+            "1;38:2:1:2:3;48:5:200",
+            Some(Color::RGB(1, 2, 3)),
+            Some(Color::Fixed(200)),
+            FontStyle::bold(),
+        );
+        assert_style(
+            "38:5:202;1",
+            Some(Color::Fixed(202)),
+            None,
+            FontStyle::bold(),
+        );
+    }
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(Some(Color::RGB(255, 0, 128)), Color::from_hex("#FF0080"));
+        assert_eq!(Some(Color::RGB(255, 0, 128)), Color::from_hex("ff0080"));
+        assert_eq!(Some(Color::RGB(0, 171, 205)), Color::from_hex("#00abcd"));
+        assert_eq!(None, Color::from_hex("#ff008"));
+        assert_eq!(None, Color::from_hex("#ff00800"));
+        assert_eq!(None, Color::from_hex("#gg0080"));
+        assert_eq!(None, Color::from_hex("+f0080"));
+        assert_eq!(None, Color::from_hex(""));
+
+        assert_eq!(
+            Some("#ff0080".to_string()),
+            Color::RGB(255, 0, 128).to_hex()
+        );
+        assert_eq!(Some("#ff0000".to_string()), Color::Fixed(196).to_hex());
+        assert_eq!(None, Color::Red.to_hex());
+    }
+
+    #[test]
+    fn visible_width() {
+        use super::visible_width;
+
+        assert_eq!(0, visible_width(""));
+        assert_eq!(5, visible_width("plain"));
+        assert_eq!(4, visible_width("\x1b[1;34mdir/\x1b[0m"));
+        assert_eq!(
+            8,
+            visible_width("\x1b[38;2;255;0;100mfile.rs\x1b[0m\x1b[1m!\x1b[m")
+        );
+        assert_eq!(6, visible_width("äöü\x1b[31m日本\x1b[0m!"));
+    }
+
+    #[test]
+    fn to_fixed() {
+        assert_eq!(Color::Fixed(231), Color::RGB(255, 255, 255).to_fixed());
+        assert_eq!(Color::Fixed(16), Color::RGB(0, 0, 0).to_fixed());
+        assert_eq!(Color::Fixed(196), Color::RGB(250, 10, 0).to_fixed());
+        assert_eq!(Color::Fixed(244), Color::RGB(128, 128, 128).to_fixed());
+        assert_eq!(Color::Fixed(232), Color::RGB(9, 8, 7).to_fixed());
+        assert_eq!(Color::Fixed(115), Color::Fixed(115).to_fixed());
+        assert_eq!(Color::Fixed(9), Color::BrightRed.to_fixed());
+    }
+
+    #[test]
+    fn to_named() {
+        assert_eq!(Color::BrightWhite, Color::RGB(255, 255, 255).to_named());
+        assert_eq!(Color::Black, Color::RGB(10, 10, 10).to_named());
+        assert_eq!(Color::Red, Color::RGB(200, 10, 0).to_named());
+        assert_eq!(Color::BrightRed, Color::Fixed(196).to_named());
+        assert_eq!(Color::Blue, Color::Fixed(4).to_named());
+        assert_eq!(Color::Cyan, Color::Cyan.to_named());
+    }
+
+    #[test]
+    fn is_default() {
+        assert!(Style::default().is_default());
+        assert!(Style::from_ansi_sequence("0")
+            .unwrap_or_default()
+            .is_default());
+        assert!(Style::from_ansi_sequence("1;22").unwrap().is_default());
+
+        assert!(!Style::from_ansi_sequence("31").unwrap().is_default());
+        assert!(!Style::from_ansi_sequence("4").unwrap().is_default());
+        assert!(!Style::from_ansi_sequence("39").unwrap().is_default());
+        assert!(!Style::from_ansi_sequence("58;5;1").unwrap().is_default());
+    }
+
+    #[test]
+    fn to_ansi_sequence() {
+        for code in &[
+            "31",
+            "1;34",
+            "3;4;38;5;115",
+            "38;2;115;3;100;48;5;4",
+            "2;91;107",
+            "7;9;30;43",
+            "4;31;58;5;202",
+            "4;58;2;1;2;3",
+            "4:3;58;5;1",
+            "1;39",
+            "31;49",
+            "39;49",
+            "31;59",
+        ] {
+            let style = Style::from_ansi_sequence(code).unwrap();
+            assert_eq!(*code, style.to_ansi_sequence());
+        }
+
+        assert_eq!("0", Style::default().to_ansi_sequence());
+
+        let style = Style {
+            underline_color: Some(Color::BrightRed),
+            ..Style::default()
+        };
+        assert_eq!("58;5;9", style.to_ansi_sequence());
+
+        let style = Style::from_ansi_sequence("1;39;49").unwrap();
+        assert_eq!("\x1b[1;39;49m", style.to_ansi_escape_sequence());
+    }
+
+    #[test]
+    fn to_ansi_escape_sequence() {
+        let style = Style::from_ansi_sequence("01;34").unwrap();
+        assert_eq!("\x1b[1;34m", style.to_ansi_escape_sequence());
+
+        let style = Style::from_ansi_sequence("38;5;115").unwrap();
+        assert_eq!("\x1b[38;5;115m", style.to_ansi_escape_sequence());
+
+        let style = Style::from_ansi_sequence("48;2;255;0;100").unwrap();
+        assert_eq!("\x1b[48;2;255;0;100m", style.to_ansi_escape_sequence());
+
+        let mut out = String::from("foo");
+        style.write_ansi_escape_sequence(&mut out);
+        assert_eq!("foo\x1b[48;2;255;0;100m", out);
+    }
+
+    #[cfg(feature = "crossterm")]
+    #[test]
+    fn to_crossterm_style() {
+        use crossterm::style::{Attribute, Color as CColor};
+
+        let style = Style::from_ansi_sequence("01;34")
+            .unwrap()
+            .to_crossterm_style();
+        assert_eq!(Some(CColor::DarkBlue), style.foreground_color);
+        assert_eq!(None, style.background_color);
+        assert!(style.attributes.has(Attribute::Bold));
+        assert!(!style.attributes.has(Attribute::Italic));
+
+        let style = Style::from_ansi_sequence("03;04;38;5;115;48;2;1;2;3")
+            .unwrap()
+            .to_crossterm_style();
+        assert_eq!(Some(CColor::AnsiValue(115)), style.foreground_color);
+        assert_eq!(
+            Some(CColor::Rgb { r: 1, g: 2, b: 3 }),
+            style.background_color
+        );
+        assert!(style.attributes.has(Attribute::Italic));
+        assert!(style.attributes.has(Attribute::Underlined));
+        assert!(!style.attributes.has(Attribute::Bold));
+    }
+
+    #[cfg(feature = "anstyle")]
+    #[test]
+    fn to_anstyle() {
+        use anstyle::{Ansi256Color, AnsiColor, Effects, RgbColor};
+
+        let style = Style::from_ansi_sequence("01;03;04;38;5;115;48;2;1;2;3;58;5;9").unwrap();
+        let anstyle = style.to_anstyle();
+        assert_eq!(
+            Some(anstyle::Color::Ansi256(Ansi256Color(115))),
+            anstyle.get_fg_color()
+        );
+        assert_eq!(
+            Some(anstyle::Color::Rgb(RgbColor(1, 2, 3))),
+            anstyle.get_bg_color()
+        );
+        assert_eq!(
+            Effects::BOLD | Effects::ITALIC | Effects::UNDERLINE,
+            anstyle.get_effects()
+        );
+        assert_eq!(
+            Some(anstyle::Color::Ansi256(Ansi256Color(9))),
+            anstyle.get_underline_color()
+        );
+        assert_eq!(style, Style::from(anstyle));
+
+        let anstyle = Style::from_ansi_sequence("94").unwrap().to_anstyle();
+        assert_eq!(Some(AnsiColor::BrightBlue.into()), anstyle.get_fg_color());
+    }
+
+    #[cfg(feature = "owo-colors")]
+    #[test]
+    fn to_owo_style() {
+        let style = Style::from_ansi_sequence("01;38;2;255;0;100").unwrap();
+        let owo_style = style.to_owo_style();
+        assert_eq!(
+            "\x1b[38;2;255;0;100;1mfoo\x1b[0m",
+            owo_style.style("foo").to_string()
+        );
+
+        let style = Style::from_ansi_sequence("4:3;38;5;115;44").unwrap();
+        let owo_style = style.to_owo_style();
+        assert_eq!(
+            "\x1b[38;5;115;44;4mfoo\x1b[0m",
+            owo_style.style("foo").to_string()
+        );
+    }
 }
@@ -0,0 +1,154 @@
+/// A trie over reversed file name suffixes. This allows us to find the matching suffix rule in
+/// time proportional to the length of the file name, instead of the number of rules.
+#[derive(Debug, Clone)]
+pub struct SuffixTrie {
+    /// All nodes of the trie. The first node is the root.
+    nodes: Vec<Node>,
+
+    /// Whether suffixes are matched case-sensitively
+    case_sensitive: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Child nodes, indexed by the next (preceding) byte of the suffix
+    children: Vec<(u8, usize)>,
+
+    /// Index of the last rule whose suffix ends at this node
+    rule: Option<usize>,
+}
+
+impl SuffixTrie {
+    pub fn new(case_sensitive: bool) -> Self {
+        SuffixTrie {
+            nodes: vec![Node::default()],
+            case_sensitive,
+        }
+    }
+
+    fn fold(&self, byte: u8) -> u8 {
+        if self.case_sensitive {
+            byte
+        } else {
+            byte.to_ascii_lowercase()
+        }
+    }
+
+    fn child(&self, node: usize, byte: u8) -> Option<usize> {
+        self.nodes[node]
+            .children
+            .iter()
+            .find(|(b, _)| *b == byte)
+            .map(|(_, child)| *child)
+    }
+
+    /// Insert a suffix for the rule with the given index. Rules must be inserted in order.
+    pub fn insert(&mut self, suffix: &[u8], rule: usize) {
+        let mut node = 0;
+
+        for &byte in suffix.iter().rev() {
+            let byte = self.fold(byte);
+            node = match self.child(node, byte) {
+                Some(child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.push((byte, child));
+                    child
+                }
+            };
+        }
+
+        self.nodes[node].rule = Some(rule);
+    }
+
+    /// Find the index of the last rule whose suffix matches the given file name.
+    pub fn find(&self, filename: &[u8]) -> Option<usize> {
+        let mut node = 0;
+        let mut rule = self.nodes[node].rule;
+
+        for &byte in filename.iter().rev() {
+            match self.child(node, self.fold(byte)) {
+                Some(child) => node = child,
+                None => break,
+            }
+            rule = rule.max(self.nodes[node].rule);
+        }
+
+        rule
+    }
+
+    /// Find the index of the rule with the longest suffix that matches the given file name. If
+    /// there are multiple rules for that suffix, the last one is used.
+    pub fn find_longest(&self, filename: &[u8]) -> Option<usize> {
+        let mut node = 0;
+        let mut rule = self.nodes[node].rule;
+
+        for &byte in filename.iter().rev() {
+            match self.child(node, self.fold(byte)) {
+                Some(child) => node = child,
+                None => break,
+            }
+            rule = self.nodes[node].rule.or(rule);
+        }
+
+        rule
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SuffixTrie;
+
+    #[test]
+    fn last_rule_wins() {
+        let mut trie = SuffixTrie::new(false);
+        trie.insert(b".gz", 0);
+        trie.insert(b".tar.gz", 1);
+        trie.insert(b".GZ", 2);
+
+        assert_eq!(Some(2), trie.find(b"foo.tar.gz"));
+        assert_eq!(Some(2), trie.find(b"foo.gz"));
+        assert_eq!(None, trie.find(b"foo.zip"));
+        assert_eq!(None, trie.find(b"gz"));
+    }
+
+    #[test]
+    fn longer_suffix_declared_later() {
+        let mut trie = SuffixTrie::new(false);
+        trie.insert(b".gz", 0);
+        trie.insert(b".tar.gz", 1);
+
+        assert_eq!(Some(1), trie.find(b"foo.tar.gz"));
+        assert_eq!(Some(0), trie.find(b"foo.gz"));
+    }
+
+    #[test]
+    fn longest_rule_wins() {
+        let mut trie = SuffixTrie::new(false);
+        trie.insert(b".tar.gz", 0);
+        trie.insert(b".gz", 1);
+        trie.insert(b".TAR.GZ", 2);
+
+        assert_eq!(Some(2), trie.find_longest(b"foo.tar.gz"));
+        assert_eq!(Some(1), trie.find_longest(b"foo.gz"));
+        assert_eq!(None, trie.find_longest(b"foo.zip"));
+    }
+
+    #[test]
+    fn case_sensitive() {
+        let mut trie = SuffixTrie::new(true);
+        trie.insert(b".C", 0);
+
+        assert_eq!(Some(0), trie.find(b"foo.C"));
+        assert_eq!(None, trie.find(b"foo.c"));
+    }
+
+    #[test]
+    fn empty_suffix() {
+        let mut trie = SuffixTrie::new(false);
+        trie.insert(b"", 0);
+
+        assert_eq!(Some(0), trie.find(b"anything"));
+    }
+}